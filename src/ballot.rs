@@ -0,0 +1,308 @@
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::Ruleset;
+
+// three-move openings for American checkers, every legal start that doesn't simply drop a man.
+// This is NOT the ACF's approved deck of 156 ballots - it includes openings the ACF bars and its
+// numbers don't match any published deck. It stands in until the official list, with its source,
+// replaces it. Squares use the standard 1-32 numbering with black's men on 1-12
+pub const BALLOTS: [&str; 246] = [
+    "9-13 21-17 5-9",
+    "9-13 21-17 6-9",
+    "9-13 21-17 10-14",
+    "9-13 21-17 10-15",
+    "9-13 21-17 11-15",
+    "9-13 21-17 11-16",
+    "9-13 21-17 12-16",
+    "9-13 22-17 13x22",
+    "9-13 22-18 6-9",
+    "9-13 22-18 10-14",
+    "9-13 22-18 10-15",
+    "9-13 22-18 11-15",
+    "9-13 22-18 11-16",
+    "9-13 22-18 12-16",
+    "9-13 22-18 13-17",
+    "9-13 23-18 5-9",
+    "9-13 23-18 6-9",
+    "9-13 23-18 10-14",
+    "9-13 23-18 10-15",
+    "9-13 23-18 11-15",
+    "9-13 23-18 11-16",
+    "9-13 23-18 12-16",
+    "9-13 23-19 5-9",
+    "9-13 23-19 6-9",
+    "9-13 23-19 10-14",
+    "9-13 23-19 10-15",
+    "9-13 23-19 11-15",
+    "9-13 23-19 11-16",
+    "9-13 24-19 5-9",
+    "9-13 24-19 6-9",
+    "9-13 24-19 10-14",
+    "9-13 24-19 10-15",
+    "9-13 24-19 11-15",
+    "9-13 24-19 11-16",
+    "9-13 24-20 5-9",
+    "9-13 24-20 6-9",
+    "9-13 24-20 10-14",
+    "9-13 24-20 10-15",
+    "9-13 24-20 11-15",
+    "9-13 24-20 11-16",
+    "9-13 24-20 12-16",
+    "9-14 22-17 5-9",
+    "9-14 22-17 6-9",
+    "9-14 22-17 10-15",
+    "9-14 22-17 11-15",
+    "9-14 22-17 11-16",
+    "9-14 22-18 5-9",
+    "9-14 22-18 6-9",
+    "9-14 22-18 10-15",
+    "9-14 22-18 11-15",
+    "9-14 22-18 11-16",
+    "9-14 22-18 12-16",
+    "9-14 22-18 14-17",
+    "9-14 23-18 14x23",
+    "9-14 23-19 5-9",
+    "9-14 23-19 6-9",
+    "9-14 23-19 10-15",
+    "9-14 23-19 11-15",
+    "9-14 23-19 11-16",
+    "9-14 23-19 14-18",
+    "9-14 24-19 5-9",
+    "9-14 24-19 6-9",
+    "9-14 24-19 10-15",
+    "9-14 24-19 11-15",
+    "9-14 24-19 11-16",
+    "9-14 24-20 5-9",
+    "9-14 24-20 6-9",
+    "9-14 24-20 10-15",
+    "9-14 24-20 11-15",
+    "9-14 24-20 11-16",
+    "10-14 22-17 6-10",
+    "10-14 22-17 7-10",
+    "10-14 22-17 9-13",
+    "10-14 22-17 11-15",
+    "10-14 22-17 11-16",
+    "10-14 22-17 14-18",
+    "10-14 22-18 6-10",
+    "10-14 22-18 7-10",
+    "10-14 22-18 9-13",
+    "10-14 22-18 11-15",
+    "10-14 22-18 11-16",
+    "10-14 22-18 12-16",
+    "10-14 23-18 14x23",
+    "10-14 23-19 6-10",
+    "10-14 23-19 7-10",
+    "10-14 23-19 9-13",
+    "10-14 23-19 11-15",
+    "10-14 23-19 11-16",
+    "10-14 23-19 14-18",
+    "10-14 24-19 6-10",
+    "10-14 24-19 7-10",
+    "10-14 24-19 9-13",
+    "10-14 24-19 11-15",
+    "10-14 24-19 11-16",
+    "10-14 24-19 14-18",
+    "10-14 24-20 6-10",
+    "10-14 24-20 7-10",
+    "10-14 24-20 9-13",
+    "10-14 24-20 11-15",
+    "10-14 24-20 11-16",
+    "10-14 24-20 14-18",
+    "10-15 21-17 6-10",
+    "10-15 21-17 7-10",
+    "10-15 21-17 9-13",
+    "10-15 21-17 9-14",
+    "10-15 21-17 11-16",
+    "10-15 21-17 15-18",
+    "10-15 22-17 6-10",
+    "10-15 22-17 7-10",
+    "10-15 22-17 9-13",
+    "10-15 22-17 9-14",
+    "10-15 22-17 11-16",
+    "10-15 22-17 15-18",
+    "10-15 22-17 15-19",
+    "10-15 22-18 15x22",
+    "10-15 23-18 6-10",
+    "10-15 23-18 7-10",
+    "10-15 23-18 9-13",
+    "10-15 23-18 9-14",
+    "10-15 23-18 11-16",
+    "10-15 23-18 12-16",
+    "10-15 23-19 6-10",
+    "10-15 23-19 7-10",
+    "10-15 23-19 9-13",
+    "10-15 23-19 9-14",
+    "10-15 23-19 11-16",
+    "10-15 23-19 15-18",
+    "10-15 24-19 15x24",
+    "10-15 24-20 6-10",
+    "10-15 24-20 7-10",
+    "10-15 24-20 9-13",
+    "10-15 24-20 9-14",
+    "10-15 24-20 11-16",
+    "10-15 24-20 12-16",
+    "10-15 24-20 15-18",
+    "10-15 24-20 15-19",
+    "11-15 21-17 7-11",
+    "11-15 21-17 8-11",
+    "11-15 21-17 9-13",
+    "11-15 21-17 9-14",
+    "11-15 21-17 10-14",
+    "11-15 21-17 15-19",
+    "11-15 22-17 7-11",
+    "11-15 22-17 8-11",
+    "11-15 22-17 9-13",
+    "11-15 22-17 9-14",
+    "11-15 22-17 10-14",
+    "11-15 22-17 15-18",
+    "11-15 22-17 15-19",
+    "11-15 22-18 15x22",
+    "11-15 23-18 7-11",
+    "11-15 23-18 8-11",
+    "11-15 23-18 9-13",
+    "11-15 23-18 9-14",
+    "11-15 23-18 10-14",
+    "11-15 23-18 12-16",
+    "11-15 23-18 15-19",
+    "11-15 23-19 7-11",
+    "11-15 23-19 8-11",
+    "11-15 23-19 9-13",
+    "11-15 23-19 9-14",
+    "11-15 23-19 10-14",
+    "11-15 24-19 15x24",
+    "11-15 24-20 7-11",
+    "11-15 24-20 8-11",
+    "11-15 24-20 9-13",
+    "11-15 24-20 9-14",
+    "11-15 24-20 10-14",
+    "11-15 24-20 12-16",
+    "11-15 24-20 15-18",
+    "11-15 24-20 15-19",
+    "11-16 21-17 7-11",
+    "11-16 21-17 8-11",
+    "11-16 21-17 9-13",
+    "11-16 21-17 9-14",
+    "11-16 21-17 10-14",
+    "11-16 21-17 10-15",
+    "11-16 21-17 16-19",
+    "11-16 21-17 16-20",
+    "11-16 22-17 7-11",
+    "11-16 22-17 8-11",
+    "11-16 22-17 9-13",
+    "11-16 22-17 9-14",
+    "11-16 22-17 10-14",
+    "11-16 22-17 10-15",
+    "11-16 22-17 16-19",
+    "11-16 22-17 16-20",
+    "11-16 22-18 7-11",
+    "11-16 22-18 8-11",
+    "11-16 22-18 9-13",
+    "11-16 22-18 9-14",
+    "11-16 22-18 10-14",
+    "11-16 22-18 10-15",
+    "11-16 22-18 16-19",
+    "11-16 22-18 16-20",
+    "11-16 23-18 7-11",
+    "11-16 23-18 8-11",
+    "11-16 23-18 9-13",
+    "11-16 23-18 9-14",
+    "11-16 23-18 10-14",
+    "11-16 23-18 10-15",
+    "11-16 23-18 16-19",
+    "11-16 23-18 16-20",
+    "11-16 23-19 16x23",
+    "11-16 24-19 7-11",
+    "11-16 24-19 8-11",
+    "11-16 24-19 9-13",
+    "11-16 24-19 9-14",
+    "11-16 24-19 10-14",
+    "11-16 24-19 10-15",
+    "11-16 24-19 16-20",
+    "11-16 24-20 7-11",
+    "11-16 24-20 8-11",
+    "11-16 24-20 9-13",
+    "11-16 24-20 9-14",
+    "11-16 24-20 10-14",
+    "11-16 24-20 10-15",
+    "11-16 24-20 16-19",
+    "12-16 21-17 8-12",
+    "12-16 21-17 9-13",
+    "12-16 21-17 9-14",
+    "12-16 21-17 16-19",
+    "12-16 21-17 16-20",
+    "12-16 22-17 8-12",
+    "12-16 22-17 16-19",
+    "12-16 22-17 16-20",
+    "12-16 22-18 8-12",
+    "12-16 22-18 9-13",
+    "12-16 22-18 9-14",
+    "12-16 22-18 10-14",
+    "12-16 22-18 16-19",
+    "12-16 22-18 16-20",
+    "12-16 23-18 8-12",
+    "12-16 23-18 9-13",
+    "12-16 23-18 9-14",
+    "12-16 23-18 10-15",
+    "12-16 23-18 11-15",
+    "12-16 23-18 16-19",
+    "12-16 23-18 16-20",
+    "12-16 23-19 16x23",
+    "12-16 24-19 8-12",
+    "12-16 24-19 16-20",
+    "12-16 24-20 8-12",
+    "12-16 24-20 9-13",
+    "12-16 24-20 10-15",
+    "12-16 24-20 11-15",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BallotChoice {
+    Random,
+    // counted from 1, the way ballots are listed
+    Numbered(usize),
+}
+
+// the moves of a ballot played from the ruleset's starting position, panics if the ballot
+// doesn't describe legal moves
+pub fn ballot_moves(rules: &dyn Ruleset, ballot: &str) -> Vec<Movedef> {
+    let mut board = rules.starting_position();
+    let mut colour: Colour = rules.first_to_move();
+    let mut moves = Vec::new();
+    for step in ballot.split_whitespace() {
+        let squares = board
+            .parse_move_squares(step)
+            .unwrap_or_else(|| panic!("{} isn't a move in ballot {}", step, ballot));
+        let movedef = rules
+            .generate_legal_moves(&board, colour)
+            .into_iter()
+            .find(|movedef| movedef.matches_squares(&squares))
+            .unwrap_or_else(|| panic!("{} isn't a legal move in ballot {}", step, ballot));
+        board.ingest_movedef(&movedef);
+        moves.push(movedef);
+        colour = colour.other();
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ballot::{ballot_moves, BALLOTS};
+    use crate::board::Board;
+    use crate::rules::American;
+
+    #[test]
+    fn every_ballot_is_three_legal_moves() {
+        for ballot in BALLOTS {
+            assert_eq!(ballot_moves(&American, ballot).len(), 3);
+        }
+    }
+
+    #[test]
+    fn ballot_squares() {
+        let b = Board::new();
+        let moves = ballot_moves(&American, "11-15 22-18 15x22");
+        assert_eq!(moves[0].start, b.get_index_from_row_col(5, 2));
+        assert_eq!(moves[0].end(), b.get_index_from_row_col(4, 3));
+        assert_eq!(moves[2].taken_pieces, vec![b.get_index_from_row_col(3, 4)]);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::board::{Board, Playable};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+
+// move generation for the 8x8 board on 32-bit bitboards, bit n - 1 standing for square n.
+// Rows of the standard diagram alternate between starting in the second column (squares 1-4,
+// 9-12, ...) and the first (5-8, 13-16, ...), which decides how far a step shifts a bit
+const EVEN_ROWS: u32 = 0x0F0F_0F0F;
+const ODD_ROWS: u32 = 0xF0F0_F0F0;
+// the first and last square of each row
+const LEFT_EDGE: u32 = 0x1111_1111;
+const RIGHT_EDGE: u32 = 0x8888_8888;
+// the rows black's and white's men are crowned on
+const BLACK_KING_ROW: u32 = 0xF000_0000;
+const WHITE_KING_ROW: u32 = 0x0000_000F;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Step {
+    DownLeft,
+    DownRight,
+    UpLeft,
+    UpRight,
+}
+
+// black's men start on 1-12 and move up the square numbers, white's move down them
+const BLACK_FORWARD: [Step; 2] = [Step::DownLeft, Step::DownRight];
+const WHITE_FORWARD: [Step; 2] = [Step::UpLeft, Step::UpRight];
+const ALL_STEPS: [Step; 4] = [Step::DownLeft, Step::DownRight, Step::UpLeft, Step::UpRight];
+
+// moves every square in `bits` one step, dropping any that would leave the board
+fn shift(bits: u32, step: Step) -> u32 {
+    match step {
+        Step::DownLeft => (bits & EVEN_ROWS) << 4 | (bits & ODD_ROWS & !LEFT_EDGE) << 3,
+        Step::DownRight => (bits & EVEN_ROWS & !RIGHT_EDGE) << 5 | (bits & ODD_ROWS) << 4,
+        Step::UpLeft => (bits & EVEN_ROWS) >> 4 | (bits & ODD_ROWS & !LEFT_EDGE) >> 5,
+        Step::UpRight => (bits & EVEN_ROWS & !RIGHT_EDGE) >> 3 | (bits & ODD_ROWS) >> 4,
+    }
+}
+
+fn steps(colour: Colour, king: bool) -> &'static [Step] {
+    match (king, colour) {
+        (true, _) => &ALL_STEPS,
+        (false, Colour::Black) => &BLACK_FORWARD,
+        (false, Colour::White) => &WHITE_FORWARD,
+    }
+}
+
+// the side's pieces and the squares around them, as the jump search needs them
+struct Position {
+    colour: Colour,
+    own: u32,
+    opponent: u32,
+    kings: u32,
+    // a man that takes a king is crowned on the spot
+    captured_king_crowns: bool,
+}
+
+impl Position {
+    fn empty(&self) -> u32 {
+        !(self.own | self.opponent)
+    }
+
+    fn king_row(&self) -> u32 {
+        match self.colour {
+            Colour::Black => BLACK_KING_ROW,
+            Colour::White => WHITE_KING_ROW,
+        }
+    }
+}
+
+// whether the board is one these bitboards can stand for
+pub fn fits(board: &Board) -> bool {
+    board.size == 8 && board.playable() == Playable::Dark
+}
+
+fn loc(board: &Board, bit: u32) -> usize {
+    board.square_loc(bit.trailing_zeros() as usize + 1).unwrap()
+}
+
+// every legal move under American rules, with capturing compulsory and a man that reaches the
+// king row during a capture stopping there. The board has to fit the bitboards
+pub fn american_moves(board: &Board, colour: Colour, captured_king_crowns: bool) -> Vec<Movedef> {
+    let position = Position {
+        colour,
+        own: board.colour_bits(colour) as u32,
+        opponent: board.colour_bits(colour.other()) as u32,
+        kings: board.king_bits() as u32,
+        captured_king_crowns,
+    };
+    let mut jumps = Vec::new();
+    let mut pieces = position.own;
+    while pieces != 0 {
+        let from = pieces & pieces.wrapping_neg();
+        pieces &= pieces - 1;
+        find_jumps(board, &position, from, from, 0, &mut Vec::new(), &mut jumps);
+    }
+    if !jumps.is_empty() {
+        return jumps;
+    }
+    let mut moves = Vec::new();
+    let mut pieces = position.own;
+    while pieces != 0 {
+        let from = pieces & pieces.wrapping_neg();
+        pieces &= pieces - 1;
+        for &step in steps(colour, position.kings & from != 0) {
+            let to = shift(from, step) & position.empty();
+            if to != 0 {
+                moves.push(Movedef::new(loc(board, from), loc(board, to)));
+            }
+        }
+    }
+    moves
+}
+
+// depth first search for the capture chains of the piece that started on `start` and has got
+// as far as `from`. Captured pieces stay on the board until the move is over
+fn find_jumps(
+    board: &Board,
+    position: &Position,
+    start: u32,
+    from: u32,
+    taken: u32,
+    path: &mut Vec<(u32, u32)>,
+    jumps: &mut Vec<Movedef>,
+) {
+    let king = position.kings & start != 0;
+    // the jumping piece has left its start square so may land there again
+    let empty = position.empty() | start;
+    let mut extended = false;
+    for &step in steps(position.colour, king) {
+        let over = shift(from, step) & position.opponent & !taken;
+        let to = shift(over, step) & empty;
+        if to == 0 {
+            continue;
+        }
+        extended = true;
+        path.push((to, over));
+        if !king && to & position.king_row() != 0 {
+            jumps.push(new_jump(board, position, start, path));
+        } else {
+            find_jumps(board, position, start, to, taken | over, path, jumps);
+        }
+        path.pop();
+    }
+    if !extended && !path.is_empty() {
+        jumps.push(new_jump(board, position, start, path));
+    }
+}
+
+fn new_jump(board: &Board, position: &Position, start: u32, path: &[(u32, u32)]) -> Movedef {
+    let mut movedef = Movedef::jump(
+        loc(board, start),
+        path.iter().map(|&(to, _)| loc(board, to)).collect(),
+        path.iter().map(|&(_, over)| loc(board, over)).collect(),
+    );
+    let took_king = path.iter().any(|&(_, over)| over & position.kings != 0);
+    movedef.crowns = position.captured_king_crowns && took_king && position.kings & start == 0;
+    movedef
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitboard::{american_moves, shift, Step};
+    use crate::board::Board;
+    use crate::movedef::Movedef;
+    use crate::player::Colour;
+    use crate::rules::{standard_moves, American};
+    use rand::seq::SliceRandom;
+
+    fn bit(square: u32) -> u32 {
+        1 << (square - 1)
+    }
+
+    #[test]
+    fn steps_stay_on_the_board() {
+        assert_eq!(shift(bit(1), Step::DownLeft), bit(5));
+        assert_eq!(shift(bit(1), Step::DownRight), bit(6));
+        assert_eq!(shift(bit(4), Step::DownRight), 0);
+        assert_eq!(shift(bit(5), Step::DownLeft), 0);
+        assert_eq!(shift(bit(5), Step::UpRight), bit(1));
+        assert_eq!(shift(bit(14), Step::UpLeft), bit(9));
+        assert_eq!(shift(bit(3), Step::UpLeft), 0);
+        assert_eq!(shift(bit(30), Step::DownLeft), 0);
+    }
+
+    fn sorted(mut moves: Vec<Movedef>) -> Vec<Movedef> {
+        moves.sort_by_key(|m| (m.start, m.path.clone()));
+        moves
+    }
+
+    #[test]
+    fn matches_the_standard_generator() {
+        // random games, comparing the two generators at every position along the way
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let mut board = Board::new();
+            let mut colour = Colour::Black;
+            for _ in 0..150 {
+                let moves = american_moves(&board, colour, false);
+                assert_eq!(
+                    sorted(moves.clone()),
+                    sorted(standard_moves(&American, &board, colour))
+                );
+                let movedef = match moves.choose(&mut rng) {
+                    Some(movedef) => movedef,
+                    None => break,
+                };
+                board.ingest_movedef(movedef);
+                colour = colour.other();
+            }
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use crate::movedef::Movedef;
-use crate::player::{Colour};
-use std::{fmt};
-use tabled::settings::{Style};
+use crate::player::Colour;
+use crate::zobrist;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use tabled::settings::Style;
 use tabled::tables::IterTable;
 
-const BOARD_SIZE: usize = 8;
-#[derive(Clone, Copy, Debug, PartialEq)]
+// the size of the American checkers board, other variants pick their own
+pub const BOARD_SIZE: usize = 8;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Piece {
     pub loc: usize,
     pub king: bool,
@@ -14,26 +17,18 @@ pub struct Piece {
 
 impl Piece {
     pub fn new(loc: usize, colour: Colour) -> Self {
-        if loc >= BOARD_SIZE.pow(2) {
-            panic!("coord larger that board size");
-        } else {
-            let me = Self {
-                loc,
-                king: false,
-                colour,
-            };
-            me
+        Self {
+            loc,
+            king: false,
+            colour,
         }
     }
 
-    pub fn update_coord(&mut self, loc: usize) -> () {
-        if loc >= BOARD_SIZE.pow(2) {
-            panic!("coord {} larger that board size", loc);
-        }
+    pub fn update_coord(&mut self, loc: usize) {
         self.loc = loc;
     }
 
-    pub fn make_king(&mut self) -> () {
+    pub fn make_king(&mut self) {
         self.king = true;
     }
 
@@ -56,15 +51,58 @@ impl Piece {
         }
     }
 }
-#[derive(Clone, Copy, PartialEq)]
+
+// the run of non-capturing moves a side has just made with one king
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KingStreak {
+    pub loc: usize,
+    pub moves: u32,
+}
+
+// what a move changed on the board, enough for `unmake_move` to put it back
+#[derive(Clone, Debug, PartialEq)]
+pub struct Undo {
+    // the piece that moved as it stood before the move, so taking it back also uncrowns it
+    mover: Piece,
+    end: usize,
+    captured: Vec<Piece>,
+    king_streaks: Option<[KingStreak; 2]>,
+    zobrist: u64,
+}
+
+// the squares pieces stand on. Most boards use the dark squares, where row + col is odd,
+// mirroring a board swaps them for the light ones and Turkish draughts uses every square
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playable {
+    Dark,
+    Light,
+    Every,
+}
+
+// a set of playable squares, bit n - 1 standing for square n in the standard numbering. On the
+// American board the low 32 bits are the usual 32-bit bitboards
+pub type Bits = u128;
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Board {
-    pub squares: [Option<Piece>; BOARD_SIZE.pow(2)],
+    pub size: usize,
+    // every square's piece, kept in step with the bitboards so callers can still index the board.
+    // Pieces are changed through `set_piece`, the bitboards don't see writes made here
+    pub squares: Vec<Option<Piece>>,
+    playable: Playable,
+    white: Bits,
+    black: Bits,
+    kings: Bits,
+    // the Zobrist key of the pieces, kept up to date as they're placed and moved
+    zobrist: u64,
+    // white's then black's king streak, only tracked for variants that limit king moves
+    pub king_streaks: Option<[KingStreak; 2]>,
 }
 
 impl fmt::Debug for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (i, square) in self.squares.iter().enumerate() {
-            match square {
+        for i in 0..self.size * self.size {
+            match self.get_piece(i) {
                 Some(piece) => write!(f, "Square {}: {:?}", i, piece)?,
                 None => write!(f, "Square {}: Empty", i)?,
             }
@@ -73,166 +111,571 @@ impl fmt::Debug for Board {
     }
 }
 
+// equal boards always have the same pieces, so their keys match
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Board {
     pub fn new() -> Self {
-        let mut b = [Option::None; BOARD_SIZE.pow(2)];
-        for i in [1, 3, 5, 7, 8, 10, 12, 14, 17, 19, 21, 23] {
-            //assign a mutable piece to the board
-            b[i] = Option::from(Piece::new(i, Colour::White));
+        Board::with_start_rows(BOARD_SIZE, 3)
+    }
+
+    pub fn empty(size: usize) -> Self {
+        if size * size / 2 > Bits::BITS as usize {
+            panic!("a {0}x{0} board has too many squares", size);
+        }
+        Self {
+            size,
+            squares: vec![None; size * size],
+            playable: Playable::Dark,
+            white: 0,
+            black: 0,
+            kings: 0,
+            zobrist: 0,
+            king_streaks: None,
+        }
+    }
+
+    // a board where the light squares can be played on as well as the dark ones
+    pub fn empty_every_square(size: usize) -> Self {
+        if size * size > Bits::BITS as usize {
+            panic!("a {0}x{0} board has too many squares", size);
         }
-        for i in [40, 42, 44, 46, 49, 51, 53, 55, 56, 58, 60, 62] {
-            b[i] = Option::from(Piece::new(i, Colour::Black));
+        Self {
+            playable: Playable::Every,
+            ..Board::empty(size)
+        }
+    }
+
+    // fills the dark squares of the first `rows` rows with white and the last `rows` with black
+    pub fn with_start_rows(size: usize, rows: usize) -> Self {
+        let mut b = Board::empty(size);
+        for i in 0..size * size {
+            let (row, col) = b.get_row_col_from_index(i);
+            if (row + col) % 2 == 0 {
+                continue;
+            }
+            if row < rows {
+                //assign a mutable piece to the board
+                b.set_piece(i, Some(Piece::new(i, Colour::White)));
+            } else if row >= size - rows {
+                b.set_piece(i, Some(Piece::new(i, Colour::Black)));
+            }
+        }
+        b
+    }
+
+    // the same board with every piece taken off, keeping the squares the variant plays on and
+    // starting its king streaks afresh if it tracks them
+    pub fn cleared(&self) -> Board {
+        Board {
+            squares: vec![None; self.squares.len()],
+            white: 0,
+            black: 0,
+            kings: 0,
+            zobrist: 0,
+            king_streaks: self.king_streaks.map(|_| Default::default()),
+            ..self.clone()
         }
-        Self { squares: b }
     }
 
-    pub fn get_row_col_from_index(loc: usize) -> (usize, usize) {
-        if loc > BOARD_SIZE * BOARD_SIZE {
+    // the same position seen in a mirror, swapping the left and right hand sides of the board
+    pub fn mirrored(&self) -> Board {
+        let playable = match self.playable {
+            Playable::Dark => Playable::Light,
+            Playable::Light => Playable::Dark,
+            Playable::Every => Playable::Every,
+        };
+        let mut b = Board {
+            playable,
+            squares: vec![None; self.squares.len()],
+            white: 0,
+            black: 0,
+            kings: 0,
+            zobrist: 0,
+            ..self.clone()
+        };
+        for colour in [Colour::White, Colour::Black] {
+            for mut piece in self.pieces(colour) {
+                let (row, col) = self.get_row_col_from_index(piece.loc);
+                let loc = self.get_index_from_row_col(row, self.size - 1 - col);
+                piece.update_coord(loc);
+                b.set_piece(loc, Some(piece));
+            }
+        }
+        b
+    }
+
+    pub fn get_row_col_from_index(&self, loc: usize) -> (usize, usize) {
+        if loc >= self.size * self.size {
             panic!("not a valid board pos")
         }
-        let row = loc / BOARD_SIZE;
-        let col = loc % BOARD_SIZE;
+        let row = loc / self.size;
+        let col = loc % self.size;
         (row, col)
     }
 
-    pub fn get_index_from_row_col(row: usize, col: usize) -> usize {
-        if row >= BOARD_SIZE || col >= BOARD_SIZE {
+    pub fn get_index_from_row_col(&self, row: usize, col: usize) -> usize {
+        if row >= self.size || col >= self.size {
             panic!("not a valid board pos")
         }
-        let mut num = row * BOARD_SIZE;
+        let mut num = row * self.size;
         num += col;
         num
     }
 
-    pub fn inside_board(x: usize, y: usize) -> bool {
-        let x_b = x < BOARD_SIZE && x >= 0;
-        let y_b = y < BOARD_SIZE && y >= 0;
-        x_b && y_b
+    pub fn inside_board(&self, x: usize, y: usize) -> bool {
+        // negative coords arrive here wrapped round to huge usizes, so one check covers both ends
+        x < self.size && y < self.size
     }
 
-    pub fn piece_get_crowned(loc: usize, colour: Colour) -> bool {
-        let (row, _col) = Board::get_row_col_from_index(loc);
+    pub fn piece_get_crowned(&self, loc: usize, colour: Colour) -> bool {
+        let (row, _col) = self.get_row_col_from_index(loc);
         match colour {
             Colour::Black => row == 0,
-            Colour::White => row == BOARD_SIZE - 1,
+            Colour::White => row == self.size - 1,
+        }
+    }
+
+    // the standard numbering of the playable squares, 1-32 on the American board. Counting
+    // starts in black's corner, which is the bottom of our board, so the diagram in the books
+    // is ours turned round. None for the light squares
+    pub fn square_number(&self, loc: usize) -> Option<usize> {
+        let (row, col) = self.get_row_col_from_index(self.size * self.size - 1 - loc);
+        let parity = match self.playable {
+            Playable::Dark => 1,
+            Playable::Light => 0,
+            Playable::Every => return Some(row * self.size + col + 1),
+        };
+        if (row + col) % 2 != parity {
+            return None;
+        }
+        Some(row * self.size / 2 + col / 2 + 1)
+    }
+
+    pub fn square_loc(&self, number: usize) -> Option<usize> {
+        if number == 0 || number > self.playable_squares() {
+            return None;
+        }
+        let parity = match self.playable {
+            Playable::Dark => 1,
+            Playable::Light => 0,
+            Playable::Every => return Some(self.size * self.size - number),
+        };
+        let half = self.size / 2;
+        let row = (number - 1) / half;
+        let col = (number - 1) % half * 2 + (row + parity) % 2;
+        Some(self.size * self.size - 1 - self.get_index_from_row_col(row, col))
+    }
+
+    // a move written the standard way, "11-15" for a step and "15x24" for a capture with
+    // every landing square of a multiple jump listed
+    pub fn move_string(&self, movedef: &Movedef) -> String {
+        let number = |loc: usize| self.square_number(loc).unwrap().to_string();
+        if !movedef.is_capture() {
+            return format!("{}-{}", number(movedef.start), number(movedef.end()));
+        }
+        let mut squares = vec![number(movedef.start)];
+        squares.extend(movedef.path.iter().map(|&loc| number(loc)));
+        squares.join("x")
+    }
+
+    // the board indices of the squares in a move typed as "11-15", "15x24" or "15x24x31"
+    pub fn parse_move_squares(&self, input: &str) -> Option<Vec<usize>> {
+        let squares = input
+            .split(['-', 'x'])
+            .map(|square| self.square_loc(square.trim().parse().ok()?))
+            .collect::<Option<Vec<usize>>>()?;
+        if squares.len() < 2 {
+            return None;
         }
+        Some(squares)
     }
 
     pub fn as_string(&self) -> String {
-        let iterator = (0..BOARD_SIZE).map(|row| {
-            (0..BOARD_SIZE).map(move |col| {
-                let x = Board::get_index_from_row_col(row, col);
-                let x = self.squares[x];
-                match x {
+        // pad the square numbers so every column is the same width
+        let w = self.playable_squares().to_string().len();
+        let iterator = (0..self.size).map(|row| {
+            (0..self.size).map(move |col| {
+                let x = self.get_index_from_row_col(row, col);
+                let number = match self.square_number(x) {
+                    Some(number) => format!("({:>w$})", number),
+                    None => String::new(),
+                };
+                match self.get_piece(x) {
                     None => {
-                        format!("({}-{})\n", row, col)
+                        format!("{}\n", number)
                     }
-                    Some(_) => {
-                        format!("({}-{})\n{}", row, col, x.unwrap().as_piece_string())
+                    Some(piece) => {
+                        format!("{}\n{}", number, piece.as_piece_string())
                     }
                 }
             })
         });
 
         let table = IterTable::new(iterator);
-        let table = table.clone().with(Style::extended());
+        let table = table.with(Style::extended());
 
         table.to_string()
     }
 
     pub fn move_piece(&mut self, old_index: usize, new_index: usize) {
-        if new_index >= BOARD_SIZE.pow(2) || self.squares[new_index] != None {
-            panic!("Invalid move from ind: {}, coord: {:?} to ind: {}, coord:{:?}", old_index, Board::get_row_col_from_index(old_index), new_index, Board::get_row_col_from_index(new_index));
+        if new_index >= self.size * self.size || self.get_piece(new_index).is_some() {
+            panic!(
+                "Invalid move from ind: {}, coord: {:?} to ind: {}, coord:{:?}",
+                old_index,
+                self.get_row_col_from_index(old_index),
+                new_index,
+                self.get_row_col_from_index(new_index)
+            );
         }
-        let piece = self.squares[old_index].unwrap();
-        let mut piece_copy = piece.clone();
+        let mut piece_copy = *self.get_piece(old_index).unwrap();
         piece_copy.update_coord(new_index);
-        self.squares[old_index] = None;
-        self.squares[new_index] = Some(piece_copy);
+        self.set_piece(old_index, None);
+        self.set_piece(new_index, Some(piece_copy));
+    }
+
+    pub fn playable(&self) -> Playable {
+        self.playable
+    }
+
+    pub fn playable_squares(&self) -> usize {
+        if self.playable == Playable::Every {
+            self.size * self.size
+        } else {
+            self.size * self.size / 2
+        }
+    }
+
+    // the bit standing for the square, none for a square that can't be played on
+    fn bit(&self, loc: usize) -> Bits {
+        match self.square_number(loc) {
+            Some(number) => 1 << (number - 1),
+            None => 0,
+        }
+    }
+
+    pub fn colour_bits(&self, colour: Colour) -> Bits {
+        match colour {
+            Colour::White => self.white,
+            Colour::Black => self.black,
+        }
+    }
+
+    pub fn king_bits(&self) -> Bits {
+        self.kings
     }
 
     pub fn get_piece(&self, loc: usize) -> Option<&Piece> {
         self.squares[loc].as_ref()
     }
 
-    pub fn get_all_colour_pieces(&self, colour: Colour) -> Vec<&Piece> {
-        let mut pieces: Vec<&Piece> = Vec::new();
-        for piece in self.squares.iter() {
-            match piece {
-                Some(piece) => {
-                    if piece.colour == colour {
-                        pieces.push(piece);
-                    }
-                }
-                None => {}
-            }
+    pub fn set_piece(&mut self, loc: usize, piece: Option<Piece>) {
+        if let Some(old) = self.squares[loc].take() {
+            self.zobrist ^= self.piece_key(&old);
         }
-        pieces
-    }
-
-    pub fn opposing_piece_between(
-        &self,
-        user_colour: Colour,
-        row_s: usize,
-        col_s: usize,
-        row_e: usize,
-        col_e: usize,
-    ) -> Option<usize> {
-        let row = (row_s + row_e) / 2;
-        let col = (col_s + col_e) / 2;
-        let index = Board::get_index_from_row_col(row, col);
-        match self.squares[index] {
-            Some(piece) => {
-                if piece.colour != user_colour {
-                    Some(index)
-                } else {
-                    None
-                }
+        let bit = self.bit(loc);
+        self.white &= !bit;
+        self.black &= !bit;
+        self.kings &= !bit;
+        let piece = match piece {
+            Some(piece) => piece,
+            None => return,
+        };
+        if bit == 0 {
+            panic!("square {} can't be played on", loc);
+        }
+        match piece.colour {
+            Colour::White => self.white |= bit,
+            Colour::Black => self.black |= bit,
+        }
+        if piece.king {
+            self.kings |= bit;
+        }
+        self.zobrist ^= self.piece_key(&piece);
+        self.squares[loc] = Some(piece);
+    }
+
+    fn piece_key(&self, piece: &Piece) -> u64 {
+        let square = self.square_number(piece.loc).unwrap() - 1;
+        zobrist::piece_key(square, piece.colour, piece.king)
+    }
+
+    // a key for the position with `to_move` to play, equal positions always share a key and
+    // different ones almost never do. The board doesn't know whose turn it is, so the side is
+    // folded in here on top of the pieces' key
+    pub fn zobrist_key(&self, to_move: Colour) -> u64 {
+        let mut key = self.zobrist ^ zobrist::side_key(to_move);
+        if let Some(streaks) = self.king_streaks {
+            for colour in [Colour::White, Colour::Black] {
+                key ^= zobrist::streak_key(colour, streaks[Board::streak_index(colour)]);
             }
-            None => None,
         }
+        key
     }
 
-    pub fn ingest_movedef(&mut self, movedef: Movedef) -> () {
-        self.move_piece(movedef.start, movedef.end);
-        let mut king = false;
-        match movedef.taken_piece {
-            Some(x) => {
-                king = self.squares[x].unwrap().king;
-                self.squares[x] = None;
+    // walks the side's pieces in square number order without allocating
+    pub fn pieces(&self, colour: Colour) -> impl Iterator<Item = Piece> + '_ {
+        let mut bits = self.colour_bits(colour);
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
             }
-            None => {}
+            let i = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(Piece {
+                loc: self.square_loc(i + 1).unwrap(),
+                king: self.kings >> i & 1 == 1,
+                colour,
+            })
+        })
+    }
+
+    pub fn get_all_colour_pieces(&self, colour: Colour) -> Vec<&Piece> {
+        self.squares
+            .iter()
+            .flatten()
+            .filter(|piece| piece.colour == colour)
+            .collect()
+    }
+
+    pub fn piece_count(&self, colour: Colour) -> u32 {
+        self.colour_bits(colour).count_ones()
+    }
+
+    fn king_count(&self, colour: Colour) -> u32 {
+        (self.colour_bits(colour) & self.kings).count_ones()
+    }
+
+    pub fn ingest_movedef(&mut self, movedef: &Movedef) {
+        let mover = *self.get_piece(movedef.start).unwrap();
+        if let Some(streaks) = self.king_streaks.as_mut() {
+            let streak = &mut streaks[Board::streak_index(mover.colour)];
+            *streak = if !mover.king || movedef.is_capture() {
+                KingStreak::default()
+            } else if streak.moves > 0 && streak.loc == movedef.start {
+                KingStreak {
+                    loc: movedef.end(),
+                    moves: streak.moves + 1,
+                }
+            } else {
+                KingStreak {
+                    loc: movedef.end(),
+                    moves: 1,
+                }
+            };
+        }
+        // taken pieces come off first, some variants let a capture finish on one of their squares
+        for &taken in movedef.taken_pieces.iter() {
+            self.set_piece(taken, None);
         }
-        let piece: &mut Piece = self.squares[movedef.end].as_mut().unwrap();
-        if king || Board::piece_get_crowned(movedef.end, piece.colour)
-        {
+        // a king can run a capture loop and finish where it started
+        if movedef.start != movedef.end() {
+            self.move_piece(movedef.start, movedef.end());
+        }
+        if movedef.crowns || self.piece_get_crowned(movedef.end(), mover.colour) {
+            let mut piece = *self.get_piece(movedef.end()).unwrap();
             piece.make_king();
+            self.set_piece(movedef.end(), Some(piece));
+        }
+    }
+
+    // plays the move in place, returning what's needed to take it back again
+    pub fn make_move(&mut self, movedef: &Movedef) -> Undo {
+        let undo = Undo {
+            mover: *self.get_piece(movedef.start).unwrap(),
+            end: movedef.end(),
+            captured: movedef
+                .taken_pieces
+                .iter()
+                .map(|&loc| *self.get_piece(loc).unwrap())
+                .collect(),
+            king_streaks: self.king_streaks,
+            zobrist: self.zobrist,
+        };
+        self.ingest_movedef(movedef);
+        undo
+    }
+
+    // takes back the last move made with `make_move`, moves have to be unmade in reverse order
+    pub fn unmake_move(&mut self, undo: Undo) {
+        // the mover comes off first, a capture can finish on the square of a piece it took
+        self.set_piece(undo.end, None);
+        for piece in undo.captured {
+            self.set_piece(piece.loc, Some(piece));
+        }
+        self.set_piece(undo.mover.loc, Some(undo.mover));
+        self.king_streaks = undo.king_streaks;
+        self.zobrist = undo.zobrist;
+    }
+
+    fn streak_index(colour: Colour) -> usize {
+        match colour {
+            Colour::White => 0,
+            Colour::Black => 1,
         }
     }
 
+    pub fn king_streak(&self, colour: Colour) -> Option<KingStreak> {
+        self.king_streaks
+            .map(|streaks| streaks[Board::streak_index(colour)])
+    }
+
     pub fn return_winner(&self) -> Option<Colour> {
-        if self.get_all_colour_pieces(Colour::Black).is_empty() {Some(Colour::White)}
-        else if self.get_all_colour_pieces(Colour::White).is_empty() {Some(Colour::Black)}
-        else {None}
+        if self.black == 0 {
+            Some(Colour::White)
+        } else if self.white == 0 {
+            Some(Colour::Black)
+        } else {
+            None
+        }
     }
 
+    // men are worth 3 and kings 5
     pub fn static_evaluation(&self, colour: Colour) -> i32 {
-        let mut score = 0;
-        for piece in self.get_all_colour_pieces(colour) {
-            if piece.king {
-                score += 5;
-            } else {
-                score += 3;
+        let value = |colour: Colour| {
+            let kings = self.king_count(colour) as i32;
+            (self.piece_count(colour) as i32 - kings) * 3 + kings * 5
+        };
+        value(colour) - value(colour.other())
+    }
+
+    // for giveaway, where every piece still on the board is a liability
+    pub fn giveaway_evaluation(&self, colour: Colour) -> i32 {
+        let mine = self.piece_count(colour) as i32;
+        let theirs = self.piece_count(colour.other()) as i32;
+        (theirs - mine) * 3
+    }
+}
+
+#[cfg(test)]
+impl Board {
+    // the board with test pieces set on it, each given as (row, col, colour, king)
+    pub(crate) fn with_pieces(mut self, pieces: &[(usize, usize, Colour, bool)]) -> Board {
+        for &(row, col, colour, king) in pieces {
+            let loc = self.get_index_from_row_col(row, col);
+            let mut piece = Piece::new(loc, colour);
+            if king {
+                piece.make_king();
             }
+            self.set_piece(loc, Some(piece));
         }
-        let colour = colour.other();
-        for piece in self.get_all_colour_pieces(colour) {
-            if piece.king {
-                score -= 5;
-            } else {
-                score -= 3;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Piece};
+    use crate::movedef::Movedef;
+    use crate::player::Colour;
+    use crate::rules::frisian::Frisian;
+    use crate::rules::{American, Ruleset};
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn standard_square_numbers() {
+        let b = Board::new();
+        // black's men start on 1-12 and white's on 21-32
+        assert_eq!(b.square_number(b.get_index_from_row_col(7, 6)), Some(1));
+        assert_eq!(b.square_number(b.get_index_from_row_col(0, 1)), Some(32));
+        assert_eq!(b.square_number(b.get_index_from_row_col(0, 0)), None);
+        for loc in 0..64 {
+            if let Some(number) = b.square_number(loc) {
+                assert_eq!(b.square_loc(number), Some(loc));
+                assert_eq!(b.get_piece(loc).is_some(), number <= 12 || number >= 21);
+            }
+        }
+        assert_eq!(b.square_loc(0), None);
+        assert_eq!(b.square_loc(33), None);
+    }
+
+    #[test]
+    fn move_notation() {
+        let b = Board::new();
+        let step = Movedef::new(b.square_loc(11).unwrap(), b.square_loc(15).unwrap());
+        assert_eq!(b.move_string(&step), "11-15");
+        let squares = [15, 24, 31].map(|n| b.square_loc(n).unwrap());
+        let jump = Movedef::jump(
+            squares[0],
+            squares[1..].to_vec(),
+            vec![b.square_loc(19).unwrap(), b.square_loc(28).unwrap()],
+        );
+        assert_eq!(b.move_string(&jump), "15x24x31");
+        assert_eq!(b.parse_move_squares("15x24x31"), Some(squares.to_vec()));
+        assert_eq!(
+            b.parse_move_squares("11-15"),
+            Some(vec![step.start, step.end()])
+        );
+        assert_eq!(b.parse_move_squares("11"), None);
+        assert_eq!(b.parse_move_squares("11-40"), None);
+        assert_eq!(
+            b.parse_move_squares("3-4"),
+            Some(vec![b.square_loc(3).unwrap(), b.square_loc(4).unwrap()])
+        );
+    }
+
+    #[test]
+    fn unmake_takes_back_a_crowning_capture() {
+        let mut b = Board::empty(8);
+        let man = b.square_loc(23).unwrap();
+        let king = b.square_loc(27).unwrap();
+        b.set_piece(man, Some(Piece::new(man, Colour::Black)));
+        let mut taken = Piece::new(king, Colour::White);
+        taken.make_king();
+        b.set_piece(king, Some(taken));
+        let before = b.clone();
+        // 23x32 takes a king and lands on the king row
+        let movedef = American.generate_legal_moves(&b, Colour::Black).remove(0);
+        let undo = b.make_move(&movedef);
+        assert!(b.get_piece(movedef.end()).unwrap().king);
+        b.unmake_move(undo);
+        assert_eq!(b, before);
+        assert_eq!(
+            b.zobrist_key(Colour::Black),
+            before.zobrist_key(Colour::Black)
+        );
+    }
+
+    #[test]
+    fn unmaking_a_game_returns_to_the_start() {
+        let mut rng = rand::thread_rng();
+        let rulesets: [&dyn Ruleset; 2] = [&American, &Frisian];
+        for rules in rulesets {
+            let mut b = rules.starting_position();
+            let start = b.clone();
+            let mut colour = rules.first_to_move();
+            let mut played = Vec::new();
+            for _ in 0..80 {
+                let moves = rules.generate_legal_moves(&b, colour);
+                let Some(movedef) = moves.choose(&mut rng) else {
+                    break;
+                };
+                played.push((b.clone(), b.make_move(movedef)));
+                colour = colour.other();
+                // the squares callers index have to agree with the bitboards
+                for side in [Colour::White, Colour::Black] {
+                    let pieces: Vec<Piece> = b.pieces(side).collect();
+                    let mut squares: Vec<Piece> =
+                        b.get_all_colour_pieces(side).into_iter().copied().collect();
+                    squares.sort_by_key(|piece| b.square_number(piece.loc));
+                    assert_eq!(pieces, squares);
+                }
+            }
+            while let Some((before, undo)) = played.pop() {
+                b.unmake_move(undo);
+                assert_eq!(b, before);
             }
+            assert_eq!(b, start);
         }
-        score
     }
 }
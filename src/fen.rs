@@ -0,0 +1,224 @@
+use crate::board::{Board, Piece};
+use crate::player::Colour;
+use crate::rules::Ruleset;
+use std::fmt;
+
+// what was wrong with a FEN string that couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    Empty,
+    BadSideToMove(String),
+    // a piece list that doesn't start with W or B
+    BadColour(String),
+    RepeatedColour(Colour),
+    BadSquare(String),
+    SquareOutOfRange(usize),
+    SquareTaken(usize),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::Empty => write!(f, "the FEN string is empty"),
+            FenError::BadSideToMove(side) => {
+                write!(f, "'{}' isn't a side to move, expected W or B", side)
+            }
+            FenError::BadColour(section) => {
+                write!(f, "'{}' doesn't start with W or B", section)
+            }
+            FenError::RepeatedColour(colour) => {
+                write!(f, "{}'s pieces are listed more than once", colour)
+            }
+            FenError::BadSquare(square) => write!(f, "'{}' isn't a square", square),
+            FenError::SquareOutOfRange(number) => {
+                write!(f, "square {} isn't on the board", number)
+            }
+            FenError::SquareTaken(number) => {
+                write!(f, "square {} has more than one piece on it", number)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+fn colour_letter(colour: Colour) -> char {
+    match colour {
+        Colour::White => 'W',
+        Colour::Black => 'B',
+    }
+}
+
+fn parse_colour(letter: &str) -> Option<Colour> {
+    match letter {
+        "W" => Some(Colour::White),
+        "B" => Some(Colour::Black),
+        _ => None,
+    }
+}
+
+// reads a position like "W:W21,22,K5:B1-3" - the side to move, then each side's pieces with kings
+// marked by a K. The [FEN "..."] tag around it from a PDN file is optional. The position is set up
+// on the ruleset's board, so the squares are numbered the way the variant numbers them
+pub fn parse_fen(fen: &str, rules: &dyn Ruleset) -> Result<(Board, Colour), FenError> {
+    let fen = fen.trim();
+    let fen = fen
+        .strip_prefix("[FEN")
+        .and_then(|fen| fen.strip_suffix(']'))
+        .unwrap_or(fen)
+        .trim()
+        .trim_matches('"')
+        .trim_end_matches('.');
+    let mut sections = fen.split(':').map(str::trim);
+    let side = sections.next().filter(|side| !side.is_empty());
+    let side = side.ok_or(FenError::Empty)?;
+    let to_move = parse_colour(side).ok_or_else(|| FenError::BadSideToMove(side.to_string()))?;
+    let mut board = rules.starting_position().cleared();
+    let mut seen = Vec::new();
+    for section in sections {
+        let colour = section
+            .get(..1)
+            .and_then(parse_colour)
+            .ok_or_else(|| FenError::BadColour(section.to_string()))?;
+        if seen.contains(&colour) {
+            return Err(FenError::RepeatedColour(colour));
+        }
+        seen.push(colour);
+        for entry in section[1..].split(',').map(str::trim) {
+            if entry.is_empty() {
+                continue;
+            }
+            let (king, squares) = match entry.strip_prefix('K') {
+                Some(squares) => (true, squares),
+                None => (false, entry),
+            };
+            let number = |text: &str| {
+                text.trim()
+                    .parse::<usize>()
+                    .map_err(|_| FenError::BadSquare(entry.to_string()))
+            };
+            // a run of squares can be given as a range, "1-12"
+            let (first, last) = match squares.split_once('-') {
+                Some((first, last)) => (number(first)?, number(last)?),
+                None => (number(squares)?, number(squares)?),
+            };
+            for number in first..=last {
+                let loc = board
+                    .square_loc(number)
+                    .ok_or(FenError::SquareOutOfRange(number))?;
+                if board.get_piece(loc).is_some() {
+                    return Err(FenError::SquareTaken(number));
+                }
+                let mut piece = Piece::new(loc, colour);
+                if king {
+                    piece.make_king();
+                }
+                board.set_piece(loc, Some(piece));
+            }
+        }
+    }
+    Ok((board, to_move))
+}
+
+pub fn to_fen(board: &Board, to_move: Colour) -> String {
+    let mut fen = colour_letter(to_move).to_string();
+    for colour in [Colour::White, Colour::Black] {
+        let mut pieces: Vec<(usize, bool)> = board
+            .get_all_colour_pieces(colour)
+            .iter()
+            .map(|piece| (board.square_number(piece.loc).unwrap(), piece.king))
+            .collect();
+        pieces.sort();
+        let squares: Vec<String> = pieces
+            .iter()
+            .map(|&(number, king)| {
+                if king {
+                    format!("K{}", number)
+                } else {
+                    number.to_string()
+                }
+            })
+            .collect();
+        fen.push_str(&format!(":{}{}", colour_letter(colour), squares.join(",")));
+    }
+    fen
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::fen::{parse_fen, to_fen, FenError};
+    use crate::player::Colour;
+    use crate::rules::{variants, American};
+
+    #[test]
+    fn reads_a_tagged_position() {
+        let (b, to_move) = parse_fen("[FEN \"W:W21,22,K5:B1,2,3\"]", &American).unwrap();
+        assert_eq!(to_move, Colour::White);
+        assert_eq!(b.get_all_colour_pieces(Colour::White).len(), 3);
+        assert_eq!(b.get_all_colour_pieces(Colour::Black).len(), 3);
+        let king = b.get_piece(b.square_loc(5).unwrap()).unwrap();
+        assert!(king.king && king.colour == Colour::White);
+        assert!(!b.get_piece(b.square_loc(1).unwrap()).unwrap().king);
+    }
+
+    #[test]
+    fn round_trips_the_start_position() {
+        let (b, to_move) = parse_fen("B:W21-32:B1-12", &American).unwrap();
+        assert_eq!(b, Board::new());
+        assert_eq!(to_move, Colour::Black);
+        assert_eq!(
+            to_fen(&b, to_move),
+            "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12"
+        );
+        let fen = "W:WK3,21:BK30";
+        assert_eq!(
+            to_fen(&parse_fen(fen, &American).unwrap().0, Colour::White),
+            fen
+        );
+    }
+
+    #[test]
+    fn malformed_strings_are_errors() {
+        assert_eq!(parse_fen("", &American), Err(FenError::Empty));
+        assert_eq!(
+            parse_fen("X:W1:B2", &American),
+            Err(FenError::BadSideToMove("X".to_string()))
+        );
+        assert_eq!(
+            parse_fen("W:R1:B2", &American),
+            Err(FenError::BadColour("R1".to_string()))
+        );
+        assert_eq!(
+            parse_fen("W:W1:W2", &American),
+            Err(FenError::RepeatedColour(Colour::White))
+        );
+        assert_eq!(
+            parse_fen("W:W1,a:B2", &American),
+            Err(FenError::BadSquare("a".to_string()))
+        );
+        assert_eq!(
+            parse_fen("W:W33:B2", &American),
+            Err(FenError::SquareOutOfRange(33))
+        );
+        assert_eq!(
+            parse_fen("W:W1:B1", &American),
+            Err(FenError::SquareTaken(1))
+        );
+    }
+
+    #[test]
+    fn every_variant_round_trips_its_start_position() {
+        for rules in variants() {
+            let start = rules.starting_position();
+            let to_move = rules.first_to_move();
+            let fen = to_fen(&start, to_move);
+            assert_eq!(
+                parse_fen(&fen, rules.as_ref()),
+                Ok((start, to_move)),
+                "{}",
+                rules.name()
+            );
+        }
+    }
+}
@@ -1,11 +1,66 @@
+use crate::ballot::{ballot_moves, BallotChoice};
 use crate::board::Board;
+use crate::fen::{parse_fen, to_fen, FenError};
+use crate::history::GameHistory;
 use crate::movedef::Movedef;
+use crate::ordering::MoveOrdering;
+use crate::outcome::{GameOutcome, WinReason};
+use crate::pdn::{today, GameRecord};
 use crate::player::Colour;
+use crate::rules::{self, American, Ruleset};
+use crate::transposition::{Bound, TableEntry, TranspositionTable};
 use rand::prelude::SliceRandom;
-use rand::seq::IteratorRandom;
 use rand::Rng;
+use std::cell::{Cell, RefCell};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, Instant};
 use std::{io, process};
-use std::time::Instant;
+
+// every finished game is added to the end of this file
+const PDN_ARCHIVE: &str = "games.pdn";
+
+// score for a won position, well clear of anything an evaluation can return. A win is scored
+// less the further into the game it comes, so quicker wins and slower losses are preferred
+const WIN_SCORE: i32 = 10_000;
+// scores past this are wins or losses the search found, never evaluations
+const WIN_THRESHOLD: i32 = WIN_SCORE / 2;
+
+// the deepest the AI looks when it's given a time to think rather than a depth
+const MAX_SEARCH_DEPTH: i32 = 64;
+
+// how hard the AI thinks about each move, a fixed number of plies or a time per move
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Difficulty {
+    Depth(i32),
+    Time(Duration),
+}
+
+impl Difficulty {
+    // a level from 1 to 4, three plies each, or a time in seconds such as "5s" or "0.5s"
+    pub fn parse(input: &str) -> Option<Difficulty> {
+        if let Some(seconds) = input.strip_suffix('s') {
+            let seconds = seconds.trim().parse::<f64>().ok()?;
+            return if seconds > 0.0 && seconds.is_finite() {
+                Some(Difficulty::Time(Duration::from_secs_f64(seconds)))
+            } else {
+                None
+            };
+        }
+        match input.parse::<i32>() {
+            Ok(level) if (1..=4).contains(&level) => Some(Difficulty::Depth(level * 3)),
+            _ => None,
+        }
+    }
+
+    // the value of the PDN Difficulty tag, the level or the time per move
+    pub fn tag(&self) -> String {
+        match self {
+            Difficulty::Depth(depth) => (depth / 3).to_string(),
+            Difficulty::Time(time) => format!("{}s", time.as_secs_f64()),
+        }
+    }
+}
 
 enum GameState {
     Initialising,
@@ -20,28 +75,60 @@ pub struct GameManager {
     board: Option<Board>,
     player_colour: Option<Colour>,
     ai_colour: Option<Colour>,
-    move_vectors: [(i32, i32); 8],
-    minimax_depth: i32,
+    rules: Box<dyn Ruleset>,
+    difficulty: Difficulty,
+    history: Option<GameHistory>,
+    // moves per side without a capture or man move before a draw, 40 under ACF rules
+    draw_move_limit: Option<u32>,
+    // the casual option turns this off, letting a side that can capture make a plain move instead
+    forced_capture: bool,
+    // how the opening ballot is picked, None to play from the usual starting position
+    ballot: Option<BallotChoice>,
+    // the number of the ballot the current game opened with
+    drawn_ballot: Option<usize>,
+    record: Option<GameRecord>,
+    outcome: Option<GameOutcome>,
+    // positions the AI has searched, kept from one move to the next. Scores are from the
+    // AI's side so the table is cleared whenever a new position is set up
+    table: RefCell<TranspositionTable>,
+    // tries the moves likeliest to be best first, which lets alpha-beta prune far more
+    move_ordering: bool,
+    ordering: RefCell<MoveOrdering>,
+    // when the AI has to stop thinking, and whether it's had to give up part way through a search
+    deadline: Cell<Option<Instant>>,
+    stopped: Cell<bool>,
+    // the depth of the last search `think` finished
+    searched_depth: Cell<i32>,
+}
+
+impl Default for GameManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<'a> GameManager {
+impl GameManager {
     pub fn new() -> GameManager {
         Self {
             game_state: GameState::Initialising,
             board: None,
             player_colour: None,
             ai_colour: None,
-            move_vectors: [
-                (1, -1),
-                (1, 1),
-                (2, -2),
-                (2, 2),
-                (-1, -1),
-                (-1, 1),
-                (-2, -2),
-                (-2, 2),
-            ],
-            minimax_depth: 3,
+            rules: Box::new(American),
+            difficulty: Difficulty::Depth(3),
+            history: None,
+            draw_move_limit: American.draw_move_limit(),
+            forced_capture: true,
+            ballot: None,
+            drawn_ballot: None,
+            record: None,
+            outcome: None,
+            table: RefCell::default(),
+            move_ordering: true,
+            ordering: RefCell::default(),
+            deadline: Cell::new(None),
+            stopped: Cell::new(false),
+            searched_depth: Cell::new(0),
         }
     }
 
@@ -49,90 +136,268 @@ impl<'a> GameManager {
         self.board = Some(board);
     }
 
-    pub fn generate_legal_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
-        let mut legal_moves: Vec<Movedef> = Vec::new();
-        let my_pieces = board.get_all_colour_pieces(colour);
-        for piece in my_pieces {
-            let coord = Board::get_row_col_from_index(piece.loc);
-            let row = coord.0 as i32;
-            let col = coord.1 as i32;
-            let piece_move_vecs = if piece.king {
-                &self.move_vectors
-            } else if colour == Colour::White {
-                &self.move_vectors[..4]
-            } else {
-                &self.move_vectors[4..]
-            };
+    // sets up a position to play or search from, with its own history for spotting draws
+    pub fn set_position(&mut self, board: Board, to_move: Colour) {
+        self.history = Some(GameHistory::new(
+            board.clone(),
+            to_move,
+            self.draw_move_limit,
+        ));
+        self.record = Some(self.new_record(&board, to_move));
+        self.board = Some(board);
+        self.table.get_mut().clear();
+    }
 
-            for move_vec in piece_move_vecs.iter() {
-                let (new_row, new_col) = (row + move_vec.0, col + move_vec.1);
-                if !Board::inside_board(new_row as usize, new_col as usize) {
-                    continue;
-                }
-                let new_index = Board::get_index_from_row_col(new_row as usize, new_col as usize);
-                let new_piece = board.get_piece(new_index);
-                if new_piece.is_some() {
-                    continue;
-                }
-                if move_vec.0.abs() == 2 {
-                    //jump
-                    match board.opposing_piece_between(
-                        colour,
-                        row as usize,
-                        col as usize,
-                        new_row as usize,
-                        new_col as usize,
-                    ) {
-                        None => {}
-                        Some(piece_to_take) => {
-                            legal_moves.push(Movedef {
-                                start: piece.loc,
-                                end: new_index,
-                                taken_piece: Option::from(piece_to_take),
-                            });
-                        }
-                    }
-                } else {
-                    //move
-                    legal_moves.push(Movedef {
-                        start: piece.loc,
-                        end: new_index,
-                        taken_piece: None,
-                    });
-                }
-            }
+    fn new_record(&self, board: &Board, to_move: Colour) -> GameRecord {
+        let mut record = GameRecord::new(self.rules.first_to_move(), to_move);
+        record.set_tag("Event", "Casual game");
+        record.set_tag("Date", &today());
+        record.set_tag("White", "?");
+        record.set_tag("Black", "?");
+        record.set_tag("Result", "*");
+        if let Some(game_type) = self.rules.pdn_game_type() {
+            record.set_tag("GameType", &game_type.to_string());
+        }
+        if *board != self.rules.starting_position() {
+            record.set_tag("SetUp", "1");
+            record.set_tag("FEN", &to_fen(board, to_move));
+        }
+        record
+    }
+
+    pub fn record(&self) -> Option<&GameRecord> {
+        self.record.as_ref()
+    }
+
+    // plays a move in the current game, keeping the board, history and record in step
+    pub fn make_move(&mut self, movedef: &Movedef) {
+        let board = self.board.as_mut().unwrap();
+        if let Some(record) = self.record.as_mut() {
+            record.push_move(board.move_string(movedef));
+        }
+        board.ingest_movedef(movedef);
+        self.history.as_mut().unwrap().push(movedef);
+    }
+
+    // adds the game to the end of a PDN file, creating it if need be
+    pub fn save_record(&self, path: &str) -> io::Result<()> {
+        let record = match self.record.as_ref() {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(record.to_pdn().as_bytes())
+    }
+
+    // sets up the position in a FEN string on the current ruleset's board, returning the side
+    // to move
+    pub fn load_fen(&mut self, fen: &str) -> Result<Colour, FenError> {
+        let (board, to_move) = parse_fen(fen, self.rules.as_ref())?;
+        self.set_position(board, to_move);
+        Ok(to_move)
+    }
+
+    pub fn fen(&self) -> Option<String> {
+        let history = self.history.as_ref()?;
+        Some(to_fen(history.board(), history.to_move()))
+    }
+
+    pub fn set_ruleset(&mut self, rules: Box<dyn Ruleset>) {
+        self.draw_move_limit = rules.draw_move_limit();
+        self.rules = rules;
+        self.table.get_mut().clear();
+    }
+
+    pub fn set_draw_move_limit(&mut self, limit: Option<u32>) {
+        self.draw_move_limit = limit;
+        self.table.get_mut().clear();
+    }
+
+    pub fn set_forced_capture(&mut self, forced: bool) {
+        self.forced_capture = forced;
+        self.table.get_mut().clear();
+    }
+
+    pub fn set_move_ordering(&mut self, on: bool) {
+        self.move_ordering = on;
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    // the memory the AI's transposition table may use, 0 to search without one
+    pub fn set_table_megabytes(&mut self, megabytes: usize) {
+        self.table = RefCell::new(TranspositionTable::new(megabytes));
+    }
+
+    pub fn set_ballot(&mut self, ballot: Option<BallotChoice>) {
+        self.ballot = ballot;
+    }
+
+    // plays the ballot's opening moves onto a fresh game, returning the number of the ballot
+    fn play_ballot(&mut self, choice: BallotChoice) -> usize {
+        let ballots = self.rules.ballots();
+        let number = match choice {
+            BallotChoice::Random => rand::thread_rng().gen_range(1..=ballots.len()),
+            BallotChoice::Numbered(number) => number,
+        };
+        for movedef in ballot_moves(self.rules.as_ref(), ballots[number - 1]) {
+            self.make_move(&movedef);
         }
-        //finally check if any jumps are available, if so remove all non-jumps
-        let contains_jump = legal_moves.iter().any(|&movedef| movedef.taken_piece.is_some());
-        if contains_jump {
-            legal_moves.retain(|&movedef| movedef.taken_piece.is_some());
+        number
+    }
+
+    // a side loses when it has no pieces left or none of its pieces can move,
+    // unless the ruleset is a misere one where that side wins instead
+    pub fn check_winner(&self, board: &Board, to_move: Colour) -> Option<Colour> {
+        let winner = if let Some(winner) = board.return_winner() {
+            winner
+        } else if self.generate_legal_moves(board, to_move).is_empty() {
+            to_move.other()
+        } else {
+            return None;
+        };
+        if self.rules.misere() {
+            Some(winner.other())
+        } else {
+            Some(winner)
+        }
+    }
+
+    pub fn check_outcome(&self, history: &GameHistory) -> Option<GameOutcome> {
+        let board = history.board();
+        if let Some(winner) = self.check_winner(board, history.to_move()) {
+            let reason = match (board.return_winner().is_some(), self.rules.misere()) {
+                (true, false) => WinReason::NoPieces,
+                (false, false) => WinReason::NoMoves,
+                (true, true) => WinReason::GaveAwayPieces,
+                (false, true) => WinReason::Blocked,
+            };
+            return Some(GameOutcome::Win(winner, reason));
+        }
+        history.draw_reason().map(GameOutcome::Draw)
+    }
+
+    // the search goes through here too, so the AI plays by the same capture rule as the player
+    pub fn generate_legal_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        let mut moves = self.rules.generate_legal_moves(board, colour);
+        if !self.forced_capture
+            && self.rules.mandatory_capture()
+            && moves.iter().any(Movedef::is_capture)
+        {
+            moves.append(&mut self.rules.quiet_moves(board, colour));
         }
-        legal_moves
+        moves
     }
 
     pub fn play_game(mut self) {
         match self.game_state {
             GameState::Initialising => {
                 println!("Welcome to checkers!");
+                let mut variants = rules::variants();
+                println!("Please choose a variant: 1-{}", variants.len());
+                for (i, variant) in variants.iter().enumerate() {
+                    println!("{}: {}", i + 1, variant.name());
+                }
+                let choice = loop {
+                    let mut input = String::new();
+                    match io::stdin().read_line(&mut input) {
+                        Ok(_) => {
+                            // Print the user's input
+                            println!("You entered: {}", input.trim());
+                            match input.trim().parse::<usize>() {
+                                Ok(choice) if choice >= 1 && choice <= variants.len() => {
+                                    break choice - 1;
+                                }
+                                _ => println!("Invalid input. Try again."),
+                            }
+                        }
+                        Err(error) => {
+                            eprintln!("Error reading input: {}", error);
+                        }
+                    }
+                };
+                self.set_ruleset(variants.swap_remove(choice));
+                if self.rules.mandatory_capture() {
+                    println!("Forced capture: on. Make captures optional for a casual game? Y/N");
+                    let mut input = String::new();
+                    while input != "Y" && input != "N" {
+                        match io::stdin().read_line(&mut input) {
+                            Ok(_) => {
+                                // Print the user's input
+                                println!("You entered: {}", input.trim());
+                                input = input.trim().to_string();
+                            }
+                            Err(error) => {
+                                eprintln!("Error reading input: {}", error);
+                            }
+                        }
+                    }
+                    self.set_forced_capture(input == "N");
+                    if self.forced_capture {
+                        println!("Forced capture: on");
+                    } else {
+                        println!("Forced capture: off");
+                    }
+                }
+                let ballots = self.rules.ballots();
+                if !ballots.is_empty() {
+                    println!(
+                        "Open with a three-move ballot? Type 'R' for a random one, a number 1-{} to choose one, 'L' to list them or 'N' for none.",
+                        ballots.len()
+                    );
+                    let ballot = loop {
+                        let mut input = String::new();
+                        match io::stdin().read_line(&mut input) {
+                            Ok(_) => {
+                                // Print the user's input
+                                println!("You entered: {}", input.trim());
+                                match input.trim() {
+                                    "R" => break Some(BallotChoice::Random),
+                                    "N" => break None,
+                                    "L" => {
+                                        for (i, ballot) in ballots.iter().enumerate() {
+                                            println!("{}: {}", i + 1, ballot);
+                                        }
+                                    }
+                                    choice => match choice.parse::<usize>() {
+                                        Ok(number) if number >= 1 && number <= ballots.len() => {
+                                            break Some(BallotChoice::Numbered(number));
+                                        }
+                                        _ => println!("Invalid input. Try again."),
+                                    },
+                                }
+                            }
+                            Err(error) => {
+                                eprintln!("Error reading input: {}", error);
+                            }
+                        }
+                    };
+                    self.set_ballot(ballot);
+                }
                 println!("Please choose a difficulty level: 1-4");
                 println!("1: Easy");
                 println!("2: Medium");
                 println!("3: Hard");
                 println!("4: Impossible");
-                let mut input = String::new();
-                while input != "1" && input != "2" && input != "3" && input != "4" {
+                println!("Or give the AI a time to think about each move, e.g. 5s");
+                self.difficulty = loop {
+                    let mut input = String::new();
                     match io::stdin().read_line(&mut input) {
                         Ok(_) => {
                             // Print the user's input
                             println!("You entered: {}", input.trim());
-                            input = input.trim().to_string();
+                            match Difficulty::parse(input.trim()) {
+                                Some(difficulty) => break difficulty,
+                                None => println!("Invalid input. Try again."),
+                            }
                         }
                         Err(error) => {
                             eprintln!("Error reading input: {}", error);
                         }
                     }
-                }
-                self.minimax_depth = input.parse::<i32>().unwrap() * 3;
+                };
                 self.game_state = GameState::Starting;
                 self.play_game();
             }
@@ -160,166 +425,108 @@ impl<'a> GameManager {
                 } else {
                     println!("Tails");
                 }
-                let won;
-                if input == "H" && result == 0 || input == "T" && result == 1 {
-                    println!("You win the coin toss! You start as white.");
-                    won = true;
+                let won = input == "H" && result == 0 || input == "T" && result == 1;
+                // the winner of the toss takes the colour that moves first
+                let first = self.rules.first_to_move();
+                if won {
+                    println!(
+                        "You win the coin toss! You start as {}.",
+                        first.to_string().to_lowercase()
+                    );
                 } else {
-                    println!("You lose the coin toss! You start as black.");
-                    won = false;
+                    println!(
+                        "You lose the coin toss! You play as {}.",
+                        first.other().to_string().to_lowercase()
+                    );
                 }
 
-                let board = Board::new();
-                self.set_board(board);
+                self.set_position(self.rules.starting_position(), first);
+                self.drawn_ballot = self.ballot.map(|choice| self.play_ballot(choice));
+                if let Some(number) = self.drawn_ballot {
+                    println!("Ballot {}: {}", number, self.rules.ballots()[number - 1]);
+                }
 
                 // set player colour
                 if won {
-                    self.player_colour = Some(Colour::White);
-                    self.ai_colour = Some(Colour::Black);
+                    self.player_colour = Some(first);
+                    self.ai_colour = Some(first.other());
+                } else {
+                    self.player_colour = Some(first.other());
+                    self.ai_colour = Some(first);
+                }
+                let record = self.record.as_mut().unwrap();
+                for colour in [Colour::White, Colour::Black] {
+                    let name = if Some(colour) == self.player_colour {
+                        "Player"
+                    } else {
+                        "AI"
+                    };
+                    record.set_tag(&colour.to_string(), name);
+                }
+                record.set_tag("Difficulty", &self.difficulty.tag());
+                // the ballot may have handed the move to the other side
+                if self.history.as_ref().unwrap().to_move() == self.player_colour.unwrap() {
                     self.game_state = GameState::PlayerTurn;
                 } else {
-                    self.player_colour = Some(Colour::Black);
-                    self.ai_colour = Some(Colour::White);
                     self.game_state = GameState::AITurn;
                 }
 
                 self.play_game();
             }
             GameState::PlayerTurn => {
-                //Select square to move from
-                let player_pieces = Board::get_all_colour_pieces(
-                    self.board.as_ref().unwrap(),
-                    self.player_colour.unwrap(),
-                );
-                let rand_piece = player_pieces.iter().choose(&mut rand::thread_rng());
-                let (rand_row, rand_col) = Board::get_row_col_from_index(rand_piece.unwrap().loc);
-                println!("{}", self.board.as_ref().unwrap().as_string());
+                let board = self.board.as_ref().unwrap();
+                let legal_moves = self.generate_legal_moves(board, self.player_colour.unwrap());
+                let example = legal_moves.choose(&mut rand::thread_rng()).unwrap();
+                println!("{}", board.as_string());
                 println!(
-                    "Your turn! Select a piece to move. E.g '{}-{}'",
-                    rand_row, rand_col
+                    "Your turn! Enter a move by its square numbers. E.g '{}'",
+                    board.move_string(example)
                 );
-                let legal_moves =
-                    self.generate_legal_moves(&self.board.unwrap(), self.player_colour.unwrap());
-                let selected_piece = loop {
-                    let mut input = String::new();
-                    match io::stdin().read_line(&mut input) {
-                        Ok(_) => {
-                            // Print the user's input
-                            println!("You entered: {}", input.trim());
-                            input = input.trim().to_string();
-                            let split: Vec<&str> = input.split("-").collect();
-                            if split.len() != 2 {
-                                println!("Invalid input. Try again.");
-                                continue;
-                            }
-                            let row = split[0].parse::<usize>();
-                            let col = split[1].parse::<usize>();
-                            if row.is_err() || col.is_err() {
-                                println!("Invalid input. Try again.");
-                                continue;
-                            }
-                            let row = row.unwrap();
-                            let col = col.unwrap();
-                            if !Board::inside_board(row, col) {
-                                println!("That's not on the board. Try again.");
-                                continue;
-                            }
-                            let index = Board::get_index_from_row_col(row, col);
-                            let piece = self.board.as_ref().unwrap().get_piece(index);
-                            match piece {
-                                Some(piece) => {
-                                    if piece.colour != self.player_colour.unwrap() {
-                                        println!("That's not your piece. Try again.");
-                                        continue;
-                                    }
-                                    if !legal_moves.iter().any(|&movedef| movedef.start == index) {
-                                        println!("That piece can't move. Try again.");
-                                        continue;
-                                    }
-                                    break piece;
-                                }
-                                None => {
-                                    println!("There's no piece there. Try again.");
-                                    continue;
-                                }
-                            }
-                        }
-                        Err(error) => {
-                            eprintln!("Error reading input: {}", error);
-                        }
-                    }
-                };
-                //Select square to move to
-                println!("Now select a square to move to");
                 let selected_move = loop {
                     let mut input = String::new();
                     match io::stdin().read_line(&mut input) {
                         Ok(_) => {
                             // Print the user's input
                             println!("You entered: {}", input.trim());
-                            input = input.trim().to_string();
-                            let split: Vec<&str> = input.split("-").collect();
-                            if split.len() != 2 {
-                                println!("Invalid input. Try again.");
-                                continue;
-                            }
-                            let row = split[0].parse::<usize>();
-                            let col = split[1].parse::<usize>();
-                            if row.is_err() || col.is_err() {
-                                println!("Invalid input. Try again.");
-                                continue;
-                            }
-                            let row = row.unwrap();
-                            let col = col.unwrap();
-                            if !Board::inside_board(row, col) {
-                                println!("That's not on the board. Try again.");
-                                continue;
-                            }
-                            if row == rand_row && col == rand_col {
-                                println!("You can't move to the same square. Try again.");
-                                continue;
-                            }
-                            let index = Board::get_index_from_row_col(row, col);
-                            let piece = self.board.as_ref().unwrap().get_piece(index);
-                            match piece {
-                                Some(_) => {
-                                    println!("There's already a piece there. Try again.");
+                            let squares = match board.parse_move_squares(input.trim()) {
+                                Some(squares) => squares,
+                                None => {
+                                    println!("Invalid input. Try again.");
                                     continue;
                                 }
-                                None => {
-                                    let mut legal = false;
-                                    let mut chosen_movedef: Option<Movedef> = None;
-                                    for movedef in legal_moves.iter() {
-                                        if movedef.start == selected_piece.loc
-                                            && movedef.end == index
-                                        {
-                                            legal = true;
-                                            chosen_movedef = Option::Some(*movedef);
-                                            break;
-                                        }
+                            };
+                            let candidates: Vec<&Movedef> = legal_moves
+                                .iter()
+                                .filter(|movedef| movedef.matches_squares(&squares))
+                                .collect();
+                            if candidates.is_empty() {
+                                match board.get_piece(squares[0]) {
+                                    Some(piece) if piece.colour == self.player_colour.unwrap() => {
+                                        println!("That's not a legal move. Try again.")
                                     }
-                                    if !legal {
-                                        println!("That's not a legal move. Try again.");
-                                        continue;
-                                    }
-                                    break chosen_movedef;
+                                    Some(_) => println!("That's not your piece. Try again."),
+                                    None => println!("There's no piece there. Try again."),
                                 }
+                                continue;
+                            }
+                            if candidates.len() == 1 {
+                                break candidates[0].clone();
                             }
+                            // more than one capture chain finishes on this square, so ask which one
+                            break self.choose_capture_route(&candidates).clone();
                         }
                         Err(error) => {
                             eprintln!("Error reading input: {}", error);
                         }
                     }
                 };
-                println!("{:?}", selected_move.unwrap());
-                self.board
-                    .as_mut()
-                    .unwrap()
-                    .ingest_movedef(selected_move.unwrap()); //give move to board and update game state
+                println!("You play {}", board.move_string(&selected_move));
+                self.make_move(&selected_move);
 
-                if self.board.as_ref().unwrap().return_winner().is_some() {
+                self.outcome = self.check_outcome(self.history.as_ref().unwrap());
+                if self.outcome.is_some() {
                     self.game_state = GameState::Ended;
-                } else{
+                } else {
                     self.game_state = GameState::AITurn;
                 }
                 println!("{}", self.board.as_ref().unwrap().as_string());
@@ -328,23 +535,59 @@ impl<'a> GameManager {
             GameState::AITurn => {
                 println!("AI's turn!");
                 let start_time = Instant::now();
-                let (best_move, nodes_evaluated) = self.get_best_move(self.minimax_depth);
+                let table_before = self.move_ordering.then(|| self.table.borrow().clone());
+                let (best_move, nodes_evaluated) = self
+                    .think(self.difficulty)
+                    .expect("AI turn started with no legal moves");
                 let end_time = Instant::now();
-                self.board.as_mut().unwrap().ingest_movedef(best_move);
+                let saving = table_before.map(|table| {
+                    let unordered = self.nodes_without_ordering(table);
+                    format!(
+                        ", {} fewer than without move ordering",
+                        unordered - nodes_evaluated
+                    )
+                });
+                println!(
+                    "AI plays {}",
+                    self.board.as_ref().unwrap().move_string(&best_move)
+                );
+                self.make_move(&best_move);
                 println!(
-                    "AI move made in {}ms, {} evaluations made.",
+                    "AI move made in {}ms, {} evaluations made{}.",
                     end_time.duration_since(start_time).as_millis(),
-                    nodes_evaluated
+                    nodes_evaluated,
+                    saving.unwrap_or_default()
                 );
-                if self.board.as_ref().unwrap().return_winner().is_some() {
+                self.outcome = self.check_outcome(self.history.as_ref().unwrap());
+                if self.outcome.is_some() {
                     self.game_state = GameState::Ended;
-                } else{
+                } else {
                     self.game_state = GameState::PlayerTurn;
                 }
                 self.play_game();
             }
             GameState::Ended => {
-                println!("Game over! - {} wins!", self.board.as_ref().unwrap().return_winner().unwrap());
+                let outcome = self.outcome.unwrap();
+                println!("Game over! - {}", outcome);
+                match outcome.winner() {
+                    Some(winner) if Some(winner) == self.player_colour => {
+                        println!("Well played, you beat the AI!")
+                    }
+                    Some(_) => println!("The AI wins this time."),
+                    None => {}
+                }
+                if let Some(number) = self.drawn_ballot {
+                    println!(
+                        "The game opened with ballot {}: {}",
+                        number,
+                        self.rules.ballots()[number - 1]
+                    );
+                }
+                self.record.as_mut().unwrap().set_result(self.outcome);
+                match self.save_record(PDN_ARCHIVE) {
+                    Ok(()) => println!("Game saved to {}", PDN_ARCHIVE),
+                    Err(error) => eprintln!("Couldn't save the game: {}", error),
+                }
                 println!("Play again? Y/N");
                 let mut input = String::new();
                 while input != "Y" && input != "N" {
@@ -370,251 +613,838 @@ impl<'a> GameManager {
         }
     }
 
+    fn choose_capture_route<'m>(&self, candidates: &[&'m Movedef]) -> &'m Movedef {
+        println!("More than one move ends there, pick the route by number:");
+        for (i, movedef) in candidates.iter().enumerate() {
+            println!(
+                "{}: {}",
+                i + 1,
+                self.board.as_ref().unwrap().move_string(movedef)
+            );
+        }
+        loop {
+            let mut input = String::new();
+            match io::stdin().read_line(&mut input) {
+                Ok(_) => match input.trim().parse::<usize>() {
+                    Ok(choice) if choice >= 1 && choice <= candidates.len() => {
+                        return candidates[choice - 1];
+                    }
+                    _ => println!("Invalid input. Try again."),
+                },
+                Err(error) => {
+                    eprintln!("Error reading input: {}", error);
+                }
+            }
+        }
+    }
+
+    // searches the position at the end of `history`, which walks the one board down the tree
+    // making and unmaking moves rather than copying it for every node
     pub fn minmax(
         &self,
-        board: Board,
         depth: i32,
         maximising_player: bool,
         mut alpha: i32,
         mut beta: i32,
+        history: &mut GameHistory,
     ) -> (i32, i32) {
         let mut nodes_evaluated = 1;
-        if depth == 0 || board.return_winner().is_some() {
-            return (
-                board.static_evaluation(self.ai_colour.unwrap()),
-                nodes_evaluated,
-            );
+        if self.out_of_time() {
+            return (0, nodes_evaluated);
+        }
+        if history.draw_reason().is_some() {
+            return (0, nodes_evaluated);
         }
-        return if maximising_player {
-            let mut max_eval = i32::MIN;
-            let legal_moves = self.generate_legal_moves(&board, self.player_colour.unwrap());
-            for movedef in legal_moves.iter() {
-                let mut new_board = board.clone();
-                new_board.ingest_movedef(*movedef);
-                let (eval, nodes) = self.minmax(new_board, depth - 1, false, alpha, beta);
-                max_eval = std::cmp::max(max_eval, eval);
+        // the AI is the maximising player, its opponent the minimising one
+        let to_move = if maximising_player {
+            self.ai_colour.unwrap()
+        } else {
+            self.player_colour.unwrap()
+        };
+        // a position already searched at least this deep may settle the score, or narrow it
+        let key = history.board().zobrist_key(to_move);
+        let ply = history.plies();
+        let stored = self.table.borrow().probe(key);
+        if let Some(entry) = stored.filter(|entry| entry.depth >= depth) {
+            let score = score_from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return (score, nodes_evaluated),
+                Bound::Lower => alpha = std::cmp::max(alpha, score),
+                Bound::Upper => beta = std::cmp::min(beta, score),
+            }
+            if beta <= alpha {
+                return (score, nodes_evaluated);
+            }
+        }
+        let (alpha_in, beta_in) = (alpha, beta);
+        let legal_moves = self.generate_legal_moves(history.board(), to_move);
+        if legal_moves.is_empty() {
+            // no pieces or no moves, either way the side to move has lost - or won if
+            // the game is misere
+            let score = WIN_SCORE - ply as i32;
+            return if maximising_player != self.rules.misere() {
+                (-score, nodes_evaluated)
+            } else {
+                (score, nodes_evaluated)
+            };
+        }
+        let mut order = self.order_moves(history, &legal_moves, to_move, stored);
+        let mut best_move = None;
+        let mut best_eval = if maximising_player {
+            i32::MIN
+        } else {
+            i32::MAX
+        };
+        // quiescence search - past the search depth only captures and promotions are played,
+        // until the position is quiet enough for the evaluation to be trusted
+        if depth <= 0 {
+            let board = history.board();
+            order.retain(|&index| {
+                legal_moves[index].is_capture()
+                    || self.promotes(board, &legal_moves[index], to_move)
+            });
+            // a side that has to capture can't stand pat on the evaluation
+            if !legal_moves.iter().all(Movedef::is_capture) {
+                best_eval = self.rules.evaluate(board, self.ai_colour.unwrap());
+                if maximising_player {
+                    alpha = std::cmp::max(alpha, best_eval);
+                } else {
+                    beta = std::cmp::min(beta, best_eval);
+                }
+                if order.is_empty() || beta <= alpha {
+                    return (best_eval, nodes_evaluated);
+                }
+            }
+        }
+        best_eval = if maximising_player {
+            let mut max_eval = best_eval;
+            for index in order {
+                let movedef = &legal_moves[index];
+                history.push(movedef);
+                let (eval, nodes) = self.minmax(depth - 1, false, alpha, beta, history);
+                history.pop();
+                if eval > max_eval {
+                    max_eval = eval;
+                    best_move = Some(index);
+                }
                 alpha = std::cmp::max(alpha, max_eval);
                 nodes_evaluated += nodes;
                 if beta <= alpha {
+                    self.ordering
+                        .borrow_mut()
+                        .record_cutoff(ply, movedef, depth);
                     break;
                 }
             }
-            (max_eval, nodes_evaluated)
+            max_eval
         } else {
-            let mut min_eval = i32::MAX;
-            let legal_moves = self.generate_legal_moves(&board, self.ai_colour.unwrap());
-            for movedef in legal_moves.iter() {
-                let mut new_board = board.clone();
-                new_board.ingest_movedef(*movedef);
-                let (eval, nodes) = self.minmax(new_board, depth - 1, true, alpha, beta);
-                min_eval = std::cmp::min(min_eval, eval);
+            let mut min_eval = best_eval;
+            for index in order {
+                let movedef = &legal_moves[index];
+                history.push(movedef);
+                let (eval, nodes) = self.minmax(depth - 1, true, alpha, beta, history);
+                history.pop();
+                if eval < min_eval {
+                    min_eval = eval;
+                    best_move = Some(index);
+                }
                 beta = std::cmp::min(beta, min_eval);
                 nodes_evaluated += nodes;
                 if beta <= alpha {
+                    self.ordering
+                        .borrow_mut()
+                        .record_cutoff(ply, movedef, depth);
                     break;
                 }
             }
-            (min_eval, nodes_evaluated)
+            min_eval
+        };
+        // the score of a search cut short by the clock can't be trusted
+        if self.stopped.get() {
+            return (best_eval, nodes_evaluated);
+        }
+        // a score outside the window it was searched with is only a bound on the true one
+        let bound = if best_eval <= alpha_in {
+            Bound::Upper
+        } else if best_eval >= beta_in {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.borrow_mut().store(TableEntry {
+            key,
+            depth,
+            bound,
+            score: score_to_table(best_eval, ply),
+            best_move: best_move.map(|index| index as u16),
+        });
+        (best_eval, nodes_evaluated)
+    }
+
+    // the order to search `moves` in, as indices into them
+    fn order_moves(
+        &self,
+        history: &GameHistory,
+        moves: &[Movedef],
+        to_move: Colour,
+        stored: Option<TableEntry>,
+    ) -> Vec<usize> {
+        if !self.move_ordering {
+            return (0..moves.len()).collect();
+        }
+        let board = history.board();
+        // stored best moves are indices into the moves in the order they're generated
+        let hash_move = stored
+            .and_then(|entry| entry.best_move)
+            .map(usize::from)
+            .filter(|&index| index < moves.len());
+        self.ordering
+            .borrow()
+            .order(moves, history.plies(), hash_move, |movedef| {
+                self.promotes(board, movedef, to_move)
+            })
+    }
+
+    // whether the move crowns the man making it
+    fn promotes(&self, board: &Board, movedef: &Movedef, colour: Colour) -> bool {
+        !board.get_piece(movedef.start).unwrap().king
+            && (movedef.crowns || self.rules.promotes(board, movedef.end(), colour))
+    }
+
+    fn reset_ordering(&self) {
+        let size = self.board.as_ref().unwrap().size;
+        self.ordering.borrow_mut().reset(size * size);
+    }
+
+    fn out_of_time(&self) -> bool {
+        if !self.stopped.get() && self.deadline.get().is_some_and(|end| Instant::now() >= end) {
+            self.stopped.set(true);
+        }
+        self.stopped.get()
+    }
+
+    // iterative deepening, searching a ply deeper each time until the difficulty's depth is
+    // reached or its time is up. A search the clock cuts short is thrown away and the move from
+    // the last one to finish played. Returns None when the AI has no legal move
+    pub fn think(&self, difficulty: Difficulty) -> Option<(Movedef, i32)> {
+        let (max_depth, time) = match difficulty {
+            Difficulty::Depth(depth) => (depth.max(1), None),
+            Difficulty::Time(time) => (MAX_SEARCH_DEPTH, Some(time)),
         };
+        let start = Instant::now();
+        let mut best_move = None;
+        let mut nodes_evaluated = 0;
+        self.stopped.set(false);
+        // what's learnt about move order carries over from one depth to the next
+        self.reset_ordering();
+        for depth in 1..=max_depth {
+            let (best_moves, nodes) = self.search_root(depth);
+            nodes_evaluated += nodes;
+            if self.stopped.get() {
+                break;
+            }
+            self.searched_depth.set(depth);
+            best_move = Some(best_moves.choose(&mut rand::thread_rng())?.clone());
+            // the clock only starts to count once the first search is done, so there's always
+            // a move to play. A search takes a few times longer than the one before it, so
+            // there's no point starting one with more than half the time gone
+            match time {
+                Some(time) if start.elapsed() * 2 > time => break,
+                Some(time) => self.deadline.set(Some(start + time)),
+                None => {}
+            }
+        }
+        self.deadline.set(None);
+        self.stopped.set(false);
+        best_move.map(|best_move| (best_move, nodes_evaluated))
     }
 
-    pub fn get_best_move(&self, depth: i32) -> (Movedef, i32) {
-        let board = self.board.unwrap().clone();
-        let legal_moves = self.generate_legal_moves(&board, self.ai_colour.unwrap());
+    // the evaluations the last search would have made without move ordering, searching as deep
+    // as it got from the table it started with. The table is put back afterwards so the
+    // comparison doesn't change how the AI plays
+    fn nodes_without_ordering(&mut self, table: TranspositionTable) -> i32 {
+        let searched = self.table.replace(table);
+        let move_ordering = self.move_ordering;
+        self.move_ordering = false;
+        let depth = Difficulty::Depth(self.searched_depth.get());
+        let nodes = self.think(depth).map_or(0, |(_, nodes)| nodes);
+        self.move_ordering = move_ordering;
+        self.table.replace(searched);
+        nodes
+    }
+
+    // returns None when the AI has no legal move, i.e. it has already lost
+    pub fn get_best_move(&self, depth: i32) -> Option<(Movedef, i32)> {
+        self.reset_ordering();
+        let (best_moves, nodes_evaluated) = self.search_root(depth);
+        let mut rng = rand::thread_rng();
+        let best_move = best_moves.as_slice().choose(&mut rng)?;
+        Some((best_move.clone(), nodes_evaluated))
+    }
+
+    // searches each of the AI's moves `depth` plies deep, returning the ones that score best
+    fn search_root(&self, depth: i32) -> (Vec<Movedef>, i32) {
+        let board = self.board.as_ref().unwrap();
+        let ai_colour = self.ai_colour.unwrap();
+        let legal_moves = self.generate_legal_moves(board, ai_colour);
         let mut best_moves = Vec::new();
+        let mut best_index = 0;
         let mut best_eval = i32::MIN;
         let mut nodes_evaluated = 0;
-        for movedef in legal_moves.iter() {
-            let mut new_board = self.board.unwrap().clone();
-            new_board.ingest_movedef(*movedef);
-            let ret = self.minmax(new_board, depth - 1, false, i32::MIN, i32::MAX);
+        let mut history = self
+            .history
+            .clone()
+            .unwrap_or_else(|| GameHistory::new(board.clone(), ai_colour, self.draw_move_limit));
+        let key = board.zobrist_key(ai_colour);
+        let stored = self.table.borrow().probe(key);
+        for index in self.order_moves(&history, &legal_moves, ai_colour, stored) {
+            let movedef = &legal_moves[index];
+            history.push(movedef);
+            // a move that can't at least tie with the best so far needn't be scored exactly,
+            // so it can be cut off as soon as it's known to be worse
+            let alpha = best_eval.saturating_sub(1);
+            let ret = self.minmax(depth - 1, false, alpha, i32::MAX, &mut history);
+            history.pop();
             let eval = ret.0;
             nodes_evaluated += ret.1;
-            if eval >= best_eval {
+            if eval > best_eval {
                 best_eval = eval;
-                best_moves.push(*movedef);
+                best_index = index;
+                best_moves.clear();
+            }
+            if eval == best_eval {
+                best_moves.push(movedef.clone());
             }
         }
-        let mut rng = rand::thread_rng();
-        let best_move = best_moves.as_slice().choose(&mut rng).unwrap();
-        (*best_move, nodes_evaluated)
+        // the next, deeper search starts with this one's best move
+        if !self.stopped.get() && !legal_moves.is_empty() {
+            self.table.borrow_mut().store(TableEntry {
+                key,
+                depth,
+                bound: Bound::Exact,
+                score: score_to_table(best_eval, history.plies()),
+                best_move: Some(best_index as u16),
+            });
+        }
+        (best_moves, nodes_evaluated)
+    }
+}
+
+// the table keeps a win's score as the distance from the stored position rather than from the
+// start of the game, so it still holds when the position comes up again at another ply
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > WIN_THRESHOLD => score.saturating_add(ply as i32),
+        score if score < -WIN_THRESHOLD => score.saturating_sub(ply as i32),
+        score => score,
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > WIN_THRESHOLD => score.saturating_sub(ply as i32),
+        score if score < -WIN_THRESHOLD => score.saturating_add(ply as i32),
+        score => score,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::board::Board;
+    use crate::ballot::BallotChoice;
+    use crate::board::{Board, Piece};
+    use crate::fen::parse_fen;
+    use crate::gamemanager::{Difficulty, GameManager, WIN_SCORE};
+    use crate::history::GameHistory;
     use crate::movedef::Movedef;
+    use crate::outcome::{DrawReason, GameOutcome, WinReason};
     use crate::player::Colour;
+    use crate::rules::giveaway::Giveaway;
+    use crate::rules::American;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn move_piece() {
         let mut b = Board::new();
-        let m = Movedef {
-            start: 23,
-            end: 30,
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
+        b.ingest_movedef(&Movedef::new(23, 30));
         println!("{}", b.as_string());
-        assert_eq!(b.squares[23], None);
-        assert_eq!(b.squares[30].unwrap().colour, Colour::White);
+        assert_eq!(b.get_piece(23), None);
+        assert_eq!(b.get_piece(30).unwrap().colour, Colour::White);
     }
     #[test]
     fn take_piece() {
         let mut b = Board::new();
-        let m = Movedef {
-            start: 23,
-            end: 30,
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        assert_eq!(b.squares[23], None);
-        assert_eq!(b.squares[30].unwrap().colour, Colour::White);
-        let m = Movedef {
-            start: 44,
-            end: 37,
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        assert_eq!(b.squares[30].unwrap().colour, Colour::White);
-        let m = Movedef {
-            start: 30,
-            end: 44,
-            taken_piece: Option::Some(37),
-        };
-        b.ingest_movedef(m);
+        b.ingest_movedef(&Movedef::new(23, 30));
+        assert_eq!(b.get_piece(23), None);
+        assert_eq!(b.get_piece(30).unwrap().colour, Colour::White);
+        b.ingest_movedef(&Movedef::new(44, 37));
+        assert_eq!(b.get_piece(30).unwrap().colour, Colour::White);
+        b.ingest_movedef(&Movedef::jump(30, vec![44], vec![37]));
         println!("{}", b.as_string());
         println!("{:?}", b);
-        assert_eq!(b.squares[30], None);
-        assert_eq!(b.squares[44].unwrap().colour, Colour::White);
-        assert_eq!(b.squares[37], None);
+        assert_eq!(b.get_piece(30), None);
+        assert_eq!(b.get_piece(44).unwrap().colour, Colour::White);
+        assert_eq!(b.get_piece(37), None);
     }
     #[test]
     fn piece_gets_crowned() {
         let mut b = Board::new();
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(4, 5),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(4, 5),
-            end: Board::get_index_from_row_col(3, 6),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(6, 3),
-            end: Board::get_index_from_row_col(5, 4),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(4, 3),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(7, 2),
-            end: Board::get_index_from_row_col(6, 3),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(2, 7),
-            end: Board::get_index_from_row_col(4, 5),
-            taken_piece: Option::from(Board::get_index_from_row_col(3, 6)),
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(4, 5),
-            end: Board::get_index_from_row_col(5, 4),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(7, 2),
-            taken_piece: Option::from(Board::get_index_from_row_col(6, 3)),
-        };
-        b.ingest_movedef(m);
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(5, 4),
+            b.get_index_from_row_col(4, 5),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(4, 5),
+            b.get_index_from_row_col(3, 6),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(6, 3),
+            b.get_index_from_row_col(5, 4),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(5, 4),
+            b.get_index_from_row_col(4, 3),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(7, 2),
+            b.get_index_from_row_col(6, 3),
+        ));
+        b.ingest_movedef(&Movedef::jump(
+            b.get_index_from_row_col(2, 7),
+            vec![b.get_index_from_row_col(4, 5)],
+            vec![b.get_index_from_row_col(3, 6)],
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(4, 5),
+            b.get_index_from_row_col(5, 4),
+        ));
+        b.ingest_movedef(&Movedef::jump(
+            b.get_index_from_row_col(5, 4),
+            vec![b.get_index_from_row_col(7, 2)],
+            vec![b.get_index_from_row_col(6, 3)],
+        ));
         println!("{}", b.as_string());
-        assert_eq!(
-            b.squares[Board::get_index_from_row_col(7, 2)].unwrap().king,
-            true
-        );
+        assert!(b.get_piece(b.get_index_from_row_col(7, 2)).unwrap().king);
     }
 
     #[test]
     fn king_moves_backwards() {
         let mut b = Board::new();
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(4, 5),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(4, 5),
-            end: Board::get_index_from_row_col(3, 6),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(6, 3),
-            end: Board::get_index_from_row_col(5, 4),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(4, 3),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(7, 2),
-            end: Board::get_index_from_row_col(6, 3),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(2, 7),
-            end: Board::get_index_from_row_col(4, 5),
-            taken_piece: Option::from(Board::get_index_from_row_col(3, 6)),
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(4, 5),
-            end: Board::get_index_from_row_col(5, 4),
-            taken_piece: None,
-        };
-        b.ingest_movedef(m);
-        let m = Movedef {
-            start: Board::get_index_from_row_col(5, 4),
-            end: Board::get_index_from_row_col(7, 2),
-            taken_piece: Option::from(Board::get_index_from_row_col(6, 3)),
-        };
-        b.ingest_movedef(m);
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(5, 4),
+            b.get_index_from_row_col(4, 5),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(4, 5),
+            b.get_index_from_row_col(3, 6),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(6, 3),
+            b.get_index_from_row_col(5, 4),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(5, 4),
+            b.get_index_from_row_col(4, 3),
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(7, 2),
+            b.get_index_from_row_col(6, 3),
+        ));
+        b.ingest_movedef(&Movedef::jump(
+            b.get_index_from_row_col(2, 7),
+            vec![b.get_index_from_row_col(4, 5)],
+            vec![b.get_index_from_row_col(3, 6)],
+        ));
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(4, 5),
+            b.get_index_from_row_col(5, 4),
+        ));
+        b.ingest_movedef(&Movedef::jump(
+            b.get_index_from_row_col(5, 4),
+            vec![b.get_index_from_row_col(7, 2)],
+            vec![b.get_index_from_row_col(6, 3)],
+        ));
         println!("{}", b.as_string());
+        assert!(b.get_piece(b.get_index_from_row_col(7, 2)).unwrap().king);
+        b.ingest_movedef(&Movedef::new(
+            b.get_index_from_row_col(7, 2),
+            b.get_index_from_row_col(6, 3),
+        ));
+        assert!(b.get_piece(b.get_index_from_row_col(6, 3)).unwrap().king);
+        println!("{}", b.as_string());
+    }
+
+    #[test]
+    fn double_jump_is_one_move() {
+        let mut b = Board::empty(8).with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (5, 4, Colour::Black, false),
+        ]);
+        let moves = GameManager::new().generate_legal_moves(&b, Colour::White);
+        assert_eq!(
+            moves,
+            vec![Movedef::jump(
+                b.get_index_from_row_col(2, 1),
+                vec![
+                    b.get_index_from_row_col(4, 3),
+                    b.get_index_from_row_col(6, 5)
+                ],
+                vec![
+                    b.get_index_from_row_col(3, 2),
+                    b.get_index_from_row_col(5, 4)
+                ],
+            )]
+        );
+        b.ingest_movedef(&moves[0]);
+        assert!(b.get_all_colour_pieces(Colour::Black).is_empty());
         assert_eq!(
-            b.squares[Board::get_index_from_row_col(7, 2)].unwrap().king,
-            true
+            b.get_piece(b.get_index_from_row_col(6, 5)).unwrap().colour,
+            Colour::White
         );
-        let m = Movedef {
-            start: Board::get_index_from_row_col(7, 2),
-            end: Board::get_index_from_row_col(6, 3),
-            taken_piece: None,
+    }
+
+    #[test]
+    fn every_capture_chain_is_generated() {
+        let b = Board::empty(8).with_pieces(&[
+            (2, 3, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (3, 4, Colour::Black, false),
+            (5, 2, Colour::Black, false),
+        ]);
+        let moves = GameManager::new().generate_legal_moves(&b, Colour::White);
+        let paths: Vec<Vec<usize>> = moves.iter().map(|m| m.path.clone()).collect();
+        assert_eq!(moves.len(), 2);
+        assert!(paths.contains(&vec![
+            b.get_index_from_row_col(4, 1),
+            b.get_index_from_row_col(6, 3)
+        ]));
+        assert!(paths.contains(&vec![b.get_index_from_row_col(4, 5)]));
+    }
+
+    #[test]
+    fn crowning_ends_the_jump() {
+        let b = Board::empty(8).with_pieces(&[
+            (5, 2, Colour::White, false),
+            (6, 3, Colour::Black, false),
+            (6, 5, Colour::Black, false),
+        ]);
+        let moves = GameManager::new().generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].path, vec![b.get_index_from_row_col(7, 4)]);
+    }
+
+    #[test]
+    fn blocked_side_loses() {
+        let b = Board::empty(8).with_pieces(&[
+            (6, 1, Colour::White, false),
+            (7, 0, Colour::Black, false),
+            (7, 2, Colour::Black, false),
+        ]);
+        let gm = GameManager::new();
+        assert_eq!(b.return_winner(), None);
+        assert_eq!(gm.check_winner(&b, Colour::White), Some(Colour::Black));
+        assert_eq!(gm.check_winner(&b, Colour::Black), None);
+    }
+
+    #[test]
+    fn blocked_ai_has_no_best_move() {
+        let mut gm = GameManager::new();
+        gm.set_board(Board::empty(8).with_pieces(&[
+            (6, 1, Colour::White, false),
+            (7, 0, Colour::Black, false),
+            (7, 2, Colour::Black, false),
+        ]));
+        gm.ai_colour = Some(Colour::White);
+        gm.player_colour = Some(Colour::Black);
+        assert!(gm.get_best_move(3).is_none());
+    }
+
+    #[test]
+    fn casual_ai_declines_a_losing_capture() {
+        // taking the black man lets black jump two white men in reply
+        let b = Board::empty(8).with_pieces(&[
+            (1, 0, Colour::White, false),
+            (2, 1, Colour::White, false),
+            (2, 3, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (4, 1, Colour::Black, false),
+            (5, 4, Colour::Black, false),
+            (6, 5, Colour::Black, false),
+        ]);
+        let mut gm = GameManager::new();
+        gm.set_board(b.clone());
+        gm.ai_colour = Some(Colour::White);
+        gm.player_colour = Some(Colour::Black);
+        let (forced, _) = gm.get_best_move(2).unwrap();
+        assert!(forced.is_capture());
+
+        gm.set_forced_capture(false);
+        let moves = gm.generate_legal_moves(&b, Colour::White);
+        assert!(moves.iter().any(|m| m.is_capture()));
+        assert!(moves.iter().any(|m| !m.is_capture()));
+        let (casual, _) = gm.get_best_move(2).unwrap();
+        assert_eq!(
+            casual,
+            Movedef::new(
+                b.get_index_from_row_col(2, 3),
+                b.get_index_from_row_col(3, 4)
+            )
+        );
+    }
+
+    #[test]
+    fn ballot_is_played_onto_the_board() {
+        let mut gm = GameManager::new();
+        let board = Board::new();
+        gm.set_board(board.clone());
+        gm.history = Some(GameHistory::new(board, Colour::Black, None));
+        let number = gm.play_ballot(BallotChoice::Numbered(1));
+        assert_eq!(number, 1);
+        let history = gm.history.as_ref().unwrap();
+        assert_eq!(history.to_move(), Colour::White);
+        assert_eq!(gm.board.as_ref(), Some(history.board()));
+        assert_ne!(history.board(), &Board::new());
+    }
+
+    #[test]
+    fn ai_searches_a_position_from_fen() {
+        let mut gm = GameManager::new();
+        let to_move = gm.load_fen("W:W18,K32:B14,K1").unwrap();
+        assert_eq!(to_move, Colour::White);
+        assert_eq!(gm.fen().unwrap(), "W:W18,K32:BK1,14");
+        gm.ai_colour = Some(Colour::White);
+        gm.player_colour = Some(Colour::Black);
+        let (best, _) = gm.get_best_move(1).unwrap();
+        let b = gm.board.as_ref().unwrap();
+        assert_eq!(b.move_string(&best), "18x9");
+        assert!(gm.load_fen("W:W18:B18").is_err());
+    }
+
+    #[test]
+    fn moves_are_recorded_for_pdn() {
+        let mut gm = GameManager::new();
+        gm.set_position(Board::new(), Colour::Black);
+        gm.play_ballot(BallotChoice::Numbered(1));
+        let record = gm.record().unwrap();
+        assert_eq!(record.moves(), ["9-13", "21-17", "5-9"]);
+        assert_eq!(record.tag("GameType"), Some("21"));
+        assert_eq!(record.tag("FEN"), None);
+        assert!(record.to_pdn().contains("1. 9-13 21-17 2. 5-9 *"));
+
+        let path = std::env::temp_dir().join("checkers_record_test.pdn");
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        gm.save_record(path).unwrap();
+        gm.save_record(path).unwrap();
+        let saved = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(saved.matches("[Event ").count(), 2);
+
+        gm.load_fen("W:W18:B14").unwrap();
+        assert_eq!(gm.record().unwrap().tag("FEN"), Some("W:W18:B14"));
+    }
+
+    #[test]
+    fn outcome_reports_move_limit_draw() {
+        let mut white = Piece::new(1, Colour::White);
+        white.make_king();
+        let mut black = Piece::new(62, Colour::Black);
+        black.make_king();
+        let mut b = Board::empty(8).with_pieces(&[]);
+        b.set_piece(1, Some(white));
+        b.set_piece(62, Some(black));
+        let gm = GameManager::new();
+        let mut history = GameHistory::new(b, Colour::White, Some(1));
+        assert_eq!(gm.check_outcome(&history), None);
+        history.push(&Movedef::new(1, 10));
+        history.push(&Movedef::new(62, 53));
+        assert_eq!(
+            gm.check_outcome(&history),
+            Some(GameOutcome::Draw(DrawReason::MoveLimit))
+        );
+    }
+
+    #[test]
+    fn giveaway_inverts_the_winner() {
+        let b = Board::empty(8).with_pieces(&[
+            (6, 1, Colour::White, false),
+            (7, 0, Colour::Black, false),
+            (7, 2, Colour::Black, false),
+        ]);
+        let mut gm = GameManager::new();
+        gm.set_ruleset(Box::new(Giveaway));
+        assert_eq!(gm.check_winner(&b, Colour::White), Some(Colour::White));
+        let history = GameHistory::new(b, Colour::White, None);
+        assert_eq!(
+            gm.check_outcome(&history),
+            Some(GameOutcome::Win(Colour::White, WinReason::Blocked))
+        );
+    }
+
+    #[test]
+    fn giveaway_ai_gives_pieces_away() {
+        // white can step next to the black man and be taken, or step away from it
+        let mut gm = GameManager::new();
+        gm.set_ruleset(Box::new(Giveaway));
+        gm.set_board(
+            Board::empty(8)
+                .with_pieces(&[(2, 1, Colour::White, false), (4, 1, Colour::Black, false)]),
+        );
+        gm.ai_colour = Some(Colour::White);
+        gm.player_colour = Some(Colour::Black);
+        let (best_move, _) = gm.get_best_move(2).unwrap();
+        assert_eq!(best_move, Movedef::new(17, 26));
+    }
+
+    #[test]
+    fn transposition_table_saves_work_without_changing_the_score() {
+        let search = |megabytes| {
+            let mut gm = GameManager::new();
+            gm.set_table_megabytes(megabytes);
+            gm.set_position(Board::new(), Colour::Black);
+            gm.ai_colour = Some(Colour::Black);
+            gm.player_colour = Some(Colour::White);
+            let mut history = gm.history.clone().unwrap();
+            gm.minmax(8, true, i32::MIN, i32::MAX, &mut history)
+        };
+        let (plain, plain_nodes) = search(0);
+        let (tabled, tabled_nodes) = search(1);
+        assert_eq!(plain, tabled);
+        assert!(tabled_nodes < plain_nodes);
+    }
+
+    #[test]
+    fn stored_wins_hold_at_another_ply() {
+        // 17-22 leaves white without a move
+        let won = "B:W25:B17,18,21,K30";
+        let search = |gm: &GameManager| {
+            let (board, to_move) = parse_fen(won, &American).unwrap();
+            let mut history = GameHistory::new(board, to_move, gm.draw_move_limit);
+            gm.minmax(1, true, i32::MIN, i32::MAX, &mut history).0
         };
-        b.ingest_movedef(m);
+        let mut gm = GameManager::new();
+        gm.ai_colour = Some(Colour::Black);
+        gm.player_colour = Some(Colour::White);
+        gm.load_fen(won).unwrap();
+        gm.reset_ordering();
+        assert_eq!(search(&gm), WIN_SCORE - 1);
+        // the same position reached two plies into the game is stored as a win one ply away
+        gm.load_fen("B:W29:B17,18,21,K26").unwrap();
+        let mut history = gm.history.clone().unwrap();
+        for (from, to) in [(26, 30), (29, 25)] {
+            let board = history.board();
+            let movedef = Movedef::new(
+                board.square_loc(from).unwrap(),
+                board.square_loc(to).unwrap(),
+            );
+            history.push(&movedef);
+        }
         assert_eq!(
-            b.squares[Board::get_index_from_row_col(6, 3)].unwrap().king,
-            true
+            gm.minmax(3, true, i32::MIN, i32::MAX, &mut history).0,
+            WIN_SCORE - 3
         );
-        println!("{}", b.as_string());
+        assert_eq!(search(&gm), WIN_SCORE - 1);
+    }
+
+    #[test]
+    fn difficulty_levels_and_times() {
+        assert_eq!(Difficulty::parse("4"), Some(Difficulty::Depth(12)));
+        assert_eq!(
+            Difficulty::parse("5s"),
+            Some(Difficulty::Time(Duration::from_secs(5)))
+        );
+        assert_eq!(
+            Difficulty::parse("0.5s"),
+            Some(Difficulty::Time(Duration::from_millis(500)))
+        );
+        assert_eq!(Difficulty::parse("5"), None);
+        assert_eq!(Difficulty::parse("0s"), None);
+        assert_eq!(Difficulty::parse("fast"), None);
+        assert_eq!(Difficulty::Depth(9).tag(), "3");
+        assert_eq!(Difficulty::Time(Duration::from_millis(1500)).tag(), "1.5s");
+    }
+
+    #[test]
+    fn thinking_stops_when_time_is_up() {
+        let mut gm = GameManager::new();
+        gm.set_position(Board::new(), Colour::Black);
+        gm.ai_colour = Some(Colour::Black);
+        gm.player_colour = Some(Colour::White);
+        // a search that's past its deadline gives up at once, whatever the depth
+        gm.reset_ordering();
+        gm.deadline.set(Some(Instant::now()));
+        let mut history = gm.history.clone().unwrap();
+        assert_eq!(gm.minmax(20, true, i32::MIN, i32::MAX, &mut history).1, 1);
+        assert!(gm.stopped.get());
+        gm.deadline.set(None);
+        // the bound is loose so a slow or busy machine can't fail it, a search that ignored the
+        // clock would still be running long after
+        let start = Instant::now();
+        let (best, _) = gm
+            .think(Difficulty::Time(Duration::from_millis(200)))
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(30));
+        assert!(gm
+            .generate_legal_moves(&Board::new(), Colour::Black)
+            .contains(&best));
+        // the clock is put away afterwards, a search by depth runs to the end
+        let (_, nodes) = gm.think(Difficulty::Depth(4)).unwrap();
+        assert!(nodes > 0);
+        assert!(!gm.stopped.get());
+    }
+
+    #[test]
+    fn move_ordering_saves_work_without_changing_the_score() {
+        let search = |ordering| {
+            let mut gm = GameManager::new();
+            gm.set_move_ordering(ordering);
+            let fen = "W:W18,19,21,23,24,26,29,30,31,32:B1,2,3,5,6,7,9,10,11,13,16";
+            let to_move = gm.load_fen(fen).unwrap();
+            gm.ai_colour = Some(to_move);
+            gm.player_colour = Some(to_move.other());
+            gm.reset_ordering();
+            let mut history = gm.history.clone().unwrap();
+            gm.minmax(8, true, i32::MIN, i32::MAX, &mut history)
+        };
+        let (unordered, unordered_nodes) = search(false);
+        let (ordered, ordered_nodes) = search(true);
+        assert_eq!(unordered, ordered);
+        assert!(ordered_nodes < unordered_nodes);
     }
 
+    #[test]
+    fn ordering_saving_leaves_the_table_alone() {
+        let mut gm = GameManager::new();
+        let fen = "W:W18,19,21,23,24,26,29,30,31,32:B1,2,3,5,6,7,9,10,11,13,16";
+        let to_move = gm.load_fen(fen).unwrap();
+        gm.ai_colour = Some(to_move);
+        gm.player_colour = Some(to_move.other());
+        let before = gm.table.borrow().clone();
+        let (_, ordered) = gm.think(Difficulty::Depth(8)).unwrap();
+        let key = gm.history.as_ref().unwrap().board().zobrist_key(to_move);
+        let searched = gm.table.borrow().probe(key);
+        let unordered = gm.nodes_without_ordering(before);
+        assert!(ordered < unordered);
+        assert_eq!(gm.table.borrow().probe(key), searched);
+        assert!(gm.move_ordering);
+    }
+
+    #[test]
+    fn pending_captures_are_played_out_before_evaluating() {
+        let mut gm = GameManager::new();
+        // white's double jump 27x18x11 is still to come when the search runs out of depth
+        gm.load_fen("W:W27,32:B4,15,23").unwrap();
+        gm.ai_colour = Some(Colour::Black);
+        gm.player_colour = Some(Colour::White);
+        gm.reset_ordering();
+        let mut history = gm.history.clone().unwrap();
+        assert_eq!(history.board().static_evaluation(Colour::Black), 3);
+        let (score, _) = gm.minmax(0, false, i32::MIN, i32::MAX, &mut history);
+        assert_eq!(score, -3);
+        // a quiet position is scored as it stands
+        gm.load_fen("B:W27,32:B4,15,22").unwrap();
+        let mut history = gm.history.clone().unwrap();
+        let (score, nodes) = gm.minmax(0, true, i32::MIN, i32::MAX, &mut history);
+        assert_eq!((score, nodes), (3, 1));
+    }
 }
@@ -0,0 +1,160 @@
+use crate::board::{Board, Undo};
+use crate::movedef::Movedef;
+use crate::outcome::DrawReason;
+use crate::player::Colour;
+
+const REPETITION_LIMIT: usize = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+struct HistoryEntry {
+    to_move: Colour,
+    // the Zobrist key of the board with `to_move` to play
+    key: u64,
+    // plies since the last capture or man move, neither of which can ever be undone
+    quiet_plies: u32,
+    // takes the board back to the position before, none for the starting position
+    undo: Option<Undo>,
+}
+
+// every position reached in a game, used to spot draws. Only the current board is kept,
+// earlier ones are got back by unmaking moves
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameHistory {
+    board: Board,
+    entries: Vec<HistoryEntry>,
+    // moves per side without a capture or a man move before the game is drawn
+    move_limit: Option<u32>,
+}
+
+impl GameHistory {
+    pub fn new(board: Board, to_move: Colour, move_limit: Option<u32>) -> Self {
+        Self {
+            entries: vec![HistoryEntry {
+                to_move,
+                key: board.zobrist_key(to_move),
+                quiet_plies: 0,
+                undo: None,
+            }],
+            board,
+            move_limit,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn to_move(&self) -> Colour {
+        self.entries.last().unwrap().to_move
+    }
+
+    // moves played since the first position
+    pub fn plies(&self) -> usize {
+        self.entries.len() - 1
+    }
+
+    pub fn push(&mut self, movedef: &Movedef) {
+        let last = self.entries.last().unwrap();
+        let man_moved = !self.board.get_piece(movedef.start).unwrap().king;
+        let quiet_plies = if man_moved || movedef.is_capture() {
+            0
+        } else {
+            last.quiet_plies + 1
+        };
+        let to_move = last.to_move.other();
+        let undo = self.board.make_move(movedef);
+        self.entries.push(HistoryEntry {
+            to_move,
+            key: self.board.zobrist_key(to_move),
+            quiet_plies,
+            undo: Some(undo),
+        });
+    }
+
+    // takes back the last move, the starting position is never removed
+    pub fn pop(&mut self) {
+        if self.entries.len() > 1 {
+            let undo = self.entries.pop().unwrap().undo.unwrap();
+            self.board.unmake_move(undo);
+        }
+    }
+
+    pub fn repetitions(&self) -> usize {
+        let last = self.entries.last().unwrap();
+        // positions before the last irreversible move can't come round again
+        self.entries
+            .iter()
+            .rev()
+            .take(last.quiet_plies as usize + 1)
+            .filter(|entry| entry.key == last.key)
+            .count()
+    }
+
+    pub fn draw_reason(&self) -> Option<DrawReason> {
+        if self.repetitions() >= REPETITION_LIMIT {
+            return Some(DrawReason::Repetition);
+        }
+        match self.move_limit {
+            Some(limit) if self.entries.last().unwrap().quiet_plies >= limit * 2 => {
+                Some(DrawReason::MoveLimit)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Piece};
+    use crate::history::GameHistory;
+    use crate::movedef::Movedef;
+    use crate::outcome::DrawReason;
+    use crate::player::Colour;
+
+    fn kings_board() -> Board {
+        let mut b = Board::empty(8);
+        for (loc, colour) in [(1, Colour::White), (62, Colour::Black)] {
+            let mut piece = Piece::new(loc, colour);
+            piece.make_king();
+            b.set_piece(loc, Some(piece));
+        }
+        b
+    }
+
+    #[test]
+    fn threefold_repetition() {
+        let mut history = GameHistory::new(kings_board(), Colour::White, None);
+        for _ in 0..2 {
+            assert_eq!(history.draw_reason(), None);
+            history.push(&Movedef::new(1, 10));
+            history.push(&Movedef::new(62, 53));
+            history.push(&Movedef::new(10, 1));
+            history.push(&Movedef::new(53, 62));
+        }
+        assert_eq!(history.repetitions(), 3);
+        assert_eq!(history.draw_reason(), Some(DrawReason::Repetition));
+        history.pop();
+        assert_eq!(history.draw_reason(), None);
+        // taking moves back puts the board back too
+        history.pop();
+        assert_eq!(history.board().get_piece(1), None);
+        assert!(history.board().get_piece(10).is_some());
+    }
+
+    #[test]
+    fn move_limit() {
+        let mut history = GameHistory::new(kings_board(), Colour::White, Some(1));
+        history.push(&Movedef::new(1, 10));
+        assert_eq!(history.draw_reason(), None);
+        history.push(&Movedef::new(62, 53));
+        assert_eq!(history.draw_reason(), Some(DrawReason::MoveLimit));
+    }
+
+    #[test]
+    fn man_move_resets_the_count() {
+        let mut history = GameHistory::new(Board::new(), Colour::White, Some(1));
+        history.push(&Movedef::new(17, 26));
+        history.push(&Movedef::new(40, 33));
+        assert_eq!(history.draw_reason(), None);
+    }
+}
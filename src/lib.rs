@@ -1,4 +1,15 @@
 pub mod board;
 pub mod player;
 pub mod gamemanager;
-pub mod movedef;
\ No newline at end of file
+pub mod movedef;
+pub mod history;
+pub mod outcome;
+pub mod rules;
+pub mod ballot;
+pub mod fen;
+pub mod pdn;
+pub mod bitboard;
+pub mod zobrist;
+pub mod perft;
+pub mod transposition;
+pub mod ordering;
\ No newline at end of file
@@ -1,6 +1,62 @@
+use checkers::fen::parse_fen;
 use checkers::gamemanager::GameManager;
+use checkers::perft::{divide, perft};
+use checkers::rules::{American, Ruleset};
+use std::env;
+use std::process;
+use std::time::Instant;
 
 fn main() {
-  GameManager::new().play_game();
+  let args: Vec<String> = env::args().skip(1).collect();
+  match args.first().map(String::as_str) {
+    Some("perft") => run_perft(&args[1..]),
+    Some("--hash") => {
+      let mut game = GameManager::new();
+      game.set_table_megabytes(parse_hash(args.get(1)));
+      game.play_game();
+    }
+    _ => GameManager::new().play_game(),
+  }
 
 }
+
+// `checkers --hash <MB>` gives the AI's transposition table that much memory, 0 to go without
+fn parse_hash(megabytes: Option<&String>) -> usize {
+  match megabytes.and_then(|megabytes| megabytes.parse().ok()) {
+    Some(megabytes) => megabytes,
+    None => {
+      eprintln!("usage: checkers --hash <MB>");
+      process::exit(2);
+    }
+  }
+}
+
+// `checkers perft <depth> [fen]` counts American checkers positions, split by the first move
+fn run_perft(args: &[String]) {
+  let depth = match args.first().and_then(|depth| depth.parse().ok()) {
+    Some(depth) => depth,
+    None => {
+      eprintln!("usage: checkers perft <depth> [fen]");
+      process::exit(2);
+    }
+  };
+  let (mut board, side) = if args.len() > 1 {
+    match parse_fen(&args[1..].join(" "), &American) {
+      Ok(position) => position,
+      Err(e) => {
+        eprintln!("Couldn't read the position: {}", e);
+        process::exit(2);
+      }
+    }
+  } else {
+    (American.starting_position(), American.first_to_move())
+  };
+  let start = Instant::now();
+  // there's no first move to split by at depth 0, just the position itself
+  let mut total = if depth == 0 { perft(&American, &mut board, side, 0) } else { 0 };
+  for (movedef, nodes) in divide(&American, &mut board, side, depth) {
+    println!("{} {}", board.move_string(&movedef), nodes);
+    total += nodes;
+  }
+  println!("\nNodes: {} ({:.2?})", total, start.elapsed());
+}
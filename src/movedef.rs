@@ -1,6 +1,51 @@
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub(crate) struct Movedef {
-    pub(crate) start: usize,
-    pub(crate) end: usize,
-    pub(crate) taken_piece: Option<usize>,
-}
\ No newline at end of file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movedef {
+    pub start: usize,
+    // every square the piece lands on, in order - the last one is where it finishes
+    pub path: Vec<usize>,
+    // every square captured along the way, in the order they were jumped
+    pub taken_pieces: Vec<usize>,
+    // crowned by the ruleset even though the move doesn't finish on the king row
+    pub crowns: bool,
+}
+
+impl Movedef {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            path: vec![end],
+            taken_pieces: Vec::new(),
+            crowns: false,
+        }
+    }
+
+    pub fn jump(start: usize, path: Vec<usize>, taken_pieces: Vec<usize>) -> Self {
+        if path.is_empty() || path.len() != taken_pieces.len() {
+            panic!("a jump needs one landing square per captured piece");
+        }
+        Self {
+            start,
+            path,
+            taken_pieces,
+            crowns: false,
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        *self.path.last().unwrap()
+    }
+
+    pub fn is_capture(&self) -> bool {
+        !self.taken_pieces.is_empty()
+    }
+
+    // whether the move goes through the squares written down for it, start first. The squares
+    // a multiple jump lands on along the way may be left out
+    pub fn matches_squares(&self, squares: &[usize]) -> bool {
+        match squares {
+            [start, end] => self.start == *start && self.end() == *end,
+            [start, path @ ..] => self.start == *start && self.path == path,
+            [] => false,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::movedef::Movedef;
+
+// sort keys, each kind of move ahead of the next whatever its score within the kind
+const HASH_MOVE: i32 = i32::MAX;
+const CAPTURE: i32 = 1 << 28;
+const KILLER: i32 = 1 << 27;
+
+// what the search has learnt about which quiet moves refute a position, used to try the
+// moves most likely to cause an alpha-beta cut-off first
+#[derive(Clone, Debug, Default)]
+pub struct MoveOrdering {
+    // the last two quiet moves to cause a cut-off at each ply, as start and end squares
+    killers: Vec<[Option<(usize, usize)>; 2]>,
+    // cut-offs caused by a quiet move from one square to another, deeper ones counting for more
+    history: Vec<i32>,
+    squares: usize,
+}
+
+impl MoveOrdering {
+    // forgets everything learnt, ready for a search on a board with `squares` squares
+    pub fn reset(&mut self, squares: usize) {
+        self.killers.clear();
+        self.history = vec![0; squares * squares];
+        self.squares = squares;
+    }
+
+    fn history_index(&self, movedef: &Movedef) -> usize {
+        movedef.start * self.squares + movedef.end()
+    }
+
+    // the order to try `moves` in as indices into it: the best move stored for the position,
+    // then captures and promotions, then killers and then quiet moves by their history score.
+    // Moves that tie keep the generator's order
+    pub fn order(
+        &self,
+        moves: &[Movedef],
+        ply: usize,
+        hash_move: Option<usize>,
+        promotes: impl Fn(&Movedef) -> bool,
+    ) -> Vec<usize> {
+        let killers = self.killers.get(ply).copied().unwrap_or_default();
+        let score = |index: usize| {
+            let movedef = &moves[index];
+            let squares = Some((movedef.start, movedef.end()));
+            if hash_move == Some(index) {
+                HASH_MOVE
+            } else if movedef.is_capture() || promotes(movedef) {
+                CAPTURE + 2 * movedef.taken_pieces.len() as i32 + promotes(movedef) as i32
+            } else if squares == killers[0] {
+                KILLER + 1
+            } else if squares == killers[1] {
+                KILLER
+            } else {
+                self.history
+                    .get(self.history_index(movedef))
+                    .map_or(0, |&score| score.min(KILLER - 1))
+            }
+        };
+        let mut order: Vec<usize> = (0..moves.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(score(index)));
+        order
+    }
+
+    // a quiet move caused a cut-off `depth` plies from the bottom of the search
+    pub fn record_cutoff(&mut self, ply: usize, movedef: &Movedef, depth: i32) {
+        if movedef.is_capture() {
+            return;
+        }
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; 2]);
+        }
+        let squares = Some((movedef.start, movedef.end()));
+        let killers = &mut self.killers[ply];
+        if killers[0] != squares {
+            killers[1] = killers[0];
+            killers[0] = squares;
+        }
+        let index = self.history_index(movedef);
+        if let Some(score) = self.history.get_mut(index) {
+            *score = score.saturating_add(depth * depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::movedef::Movedef;
+    use crate::ordering::MoveOrdering;
+
+    #[test]
+    fn moves_come_in_order_of_promise() {
+        let mut ordering = MoveOrdering::default();
+        ordering.reset(64);
+        let moves = vec![
+            Movedef::new(1, 10),
+            Movedef::new(3, 12),
+            Movedef::new(5, 14),
+            Movedef::jump(7, vec![21], vec![14]),
+            Movedef::new(40, 33),
+            Movedef::new(42, 35),
+        ];
+        let promotes = |movedef: &Movedef| movedef.end() == 35;
+        assert_eq!(
+            ordering.order(&moves, 0, None, promotes),
+            vec![3, 5, 0, 1, 2, 4]
+        );
+        // killers at the ply ahead of the rest of the quiet moves, the latest first
+        ordering.record_cutoff(0, &moves[2], 1);
+        ordering.record_cutoff(0, &moves[1], 1);
+        assert_eq!(
+            ordering.order(&moves, 0, None, promotes),
+            vec![3, 5, 1, 2, 0, 4]
+        );
+        // another ply only has the history score to go on, the deeper cut-off wins
+        ordering.record_cutoff(1, &moves[4], 3);
+        assert_eq!(
+            ordering.order(&moves, 2, Some(0), promotes),
+            vec![0, 3, 5, 4, 1, 2]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::player::Colour;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WinReason {
+    NoPieces,
+    NoMoves,
+    // the winning ways in giveaway, where the aim is to lose all your pieces or get stuck
+    GaveAwayPieces,
+    Blocked,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DrawReason {
+    Repetition,
+    MoveLimit,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameOutcome {
+    Win(Colour, WinReason),
+    Draw(DrawReason),
+}
+
+impl GameOutcome {
+    pub fn winner(&self) -> Option<Colour> {
+        match self {
+            GameOutcome::Win(colour, _) => Some(*colour),
+            GameOutcome::Draw(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameOutcome::Win(colour, WinReason::NoPieces) => {
+                write!(f, "{} wins! {} has no pieces left.", colour, colour.other())
+            }
+            GameOutcome::Win(colour, WinReason::NoMoves) => {
+                write!(f, "{} wins! {} has no legal moves.", colour, colour.other())
+            }
+            GameOutcome::Win(colour, WinReason::GaveAwayPieces) => {
+                write!(f, "{0} wins! {0} has given away all its pieces.", colour)
+            }
+            GameOutcome::Win(colour, WinReason::Blocked) => {
+                write!(f, "{0} wins! {0} has no legal moves left.", colour)
+            }
+            GameOutcome::Draw(DrawReason::Repetition) => {
+                write!(f, "Draw! The same position has been repeated three times.")
+            }
+            GameOutcome::Draw(DrawReason::MoveLimit) => {
+                write!(f, "Draw! Too many moves without a capture or a man moving.")
+            }
+        }
+    }
+}
@@ -0,0 +1,539 @@
+use crate::fen::{parse_fen, FenError};
+use crate::history::GameHistory;
+use crate::movedef::Movedef;
+use crate::outcome::GameOutcome;
+use crate::player::Colour;
+use crate::rules::Ruleset;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// PDN move text is wrapped to lines no longer than this
+const LINE_WIDTH: usize = 80;
+
+// a game as it's written in Portable Draughts Notation, its tags and the moves played
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    tags: Vec<(String, String)>,
+    // the colour that moves first under the ruleset, each numbered move starts with it
+    first_mover: Colour,
+    // the colour that made the first recorded move, not the first mover when set up from a FEN
+    start_colour: Colour,
+    moves: Vec<String>,
+}
+
+impl GameRecord {
+    pub fn new(first_mover: Colour, start_colour: Colour) -> Self {
+        Self {
+            tags: Vec::new(),
+            first_mover,
+            start_colour,
+            moves: Vec::new(),
+        }
+    }
+
+    // tags are written in the order they were first set
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // a move in standard notation, e.g. "11-15"
+    pub fn push_move(&mut self, notation: String) {
+        self.moves.push(notation);
+    }
+
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    pub fn set_result(&mut self, outcome: Option<GameOutcome>) {
+        self.set_tag("Result", result_string(outcome));
+    }
+
+    pub fn to_pdn(&self) -> String {
+        let mut pdn = String::new();
+        for (name, value) in self.tags.iter() {
+            pdn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('"', "'")));
+        }
+        pdn.push('\n');
+        let mut tokens = Vec::new();
+        // a game that starts with the second player's move is numbered "1..." to show it
+        let offset = usize::from(self.start_colour != self.first_mover);
+        for (i, notation) in self.moves.iter().enumerate() {
+            let ply = i + offset;
+            if ply % 2 == 0 {
+                tokens.push(format!("{}.", ply / 2 + 1));
+            } else if i == 0 {
+                tokens.push(format!("{}...", ply / 2 + 1));
+            }
+            tokens.push(notation.clone());
+        }
+        tokens.push(self.tag("Result").unwrap_or("*").to_string());
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + token.len() + 1 > LINE_WIDTH {
+                pdn.push_str(&line);
+                pdn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pdn.push_str(&line);
+        pdn.push_str("\n\n");
+        pdn
+    }
+}
+
+pub fn result_string(outcome: Option<GameOutcome>) -> &'static str {
+    match outcome {
+        Some(GameOutcome::Win(Colour::White, _)) => "1-0",
+        Some(GameOutcome::Win(Colour::Black, _)) => "0-1",
+        Some(GameOutcome::Draw(_)) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+// today's date the way PDN writes it, "2023.12.01"
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+    // days since 1970 to a calendar date, counting in 400 year eras that start on 1st March
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+// what went wrong reading or replaying a PDN game
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdnErrorReason {
+    UnclosedTag,
+    UnclosedComment,
+    UnclosedVariation,
+    UnexpectedToken(String),
+    BadFen(FenError),
+    // a move naming squares that aren't on the board
+    BadSquare(String),
+    NoPiece(String),
+    NotYourPiece(String),
+    IllegalMove(String),
+    // a multiple jump written without enough of its squares to tell which route it took
+    AmbiguousMove(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdnError {
+    // which game in the file, named from its tags where it has them
+    pub game: String,
+    // the numbered move the problem is in, None if it's before the moves start
+    pub move_number: Option<usize>,
+    pub reason: PdnErrorReason,
+}
+
+impl fmt::Display for PdnErrorReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdnErrorReason::UnclosedTag => write!(f, "a tag is missing its closing ']'"),
+            PdnErrorReason::UnclosedComment => write!(f, "a comment is missing its closing '}}'"),
+            PdnErrorReason::UnclosedVariation => {
+                write!(f, "a variation is missing its closing ')'")
+            }
+            PdnErrorReason::UnexpectedToken(token) => write!(f, "'{}' isn't a move", token),
+            PdnErrorReason::BadFen(error) => write!(f, "bad FEN tag, {}", error),
+            PdnErrorReason::BadSquare(notation) => {
+                write!(f, "{} names a square that isn't on the board", notation)
+            }
+            PdnErrorReason::NoPiece(notation) => {
+                write!(f, "{} starts from an empty square", notation)
+            }
+            PdnErrorReason::NotYourPiece(notation) => {
+                write!(f, "{} moves the other side's piece", notation)
+            }
+            PdnErrorReason::IllegalMove(notation) => write!(f, "{} isn't a legal move", notation),
+            PdnErrorReason::AmbiguousMove(notation) => {
+                write!(f, "{} could be more than one capture", notation)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PdnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.move_number {
+            Some(number) => write!(f, "{}, move {}: {}", self.game, number, self.reason),
+            None => write!(f, "{}: {}", self.game, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for PdnError {}
+
+const RESULTS: [&str; 7] = ["1-0", "0-1", "1/2-1/2", "2-0", "0-2", "1-1", "*"];
+
+impl GameRecord {
+    // the players and event from the tags, to tell games in a file apart
+    pub fn name(&self) -> String {
+        format!(
+            "{} vs {} ({})",
+            self.tag("White").unwrap_or("?"),
+            self.tag("Black").unwrap_or("?"),
+            self.tag("Event").unwrap_or("?")
+        )
+    }
+
+    // the number of the move the ply at `index` belongs to
+    fn move_number(&self, index: usize) -> usize {
+        (index + usize::from(self.start_colour != self.first_mover)) / 2 + 1
+    }
+
+    // plays the game through from its starting position, checking every move is legal
+    pub fn replay(&self, rules: &dyn Ruleset) -> Result<GameHistory, PdnError> {
+        let error = |move_number, reason| PdnError {
+            game: self.name(),
+            move_number,
+            reason,
+        };
+        let (board, to_move) = match self.tag("FEN") {
+            Some(fen) => parse_fen(fen, rules)
+                .map_err(|fen_error| error(None, PdnErrorReason::BadFen(fen_error)))?,
+            None => (rules.starting_position(), rules.first_to_move()),
+        };
+        let mut history = GameHistory::new(board, to_move, rules.draw_move_limit());
+        for (i, notation) in self.moves.iter().enumerate() {
+            let error = |reason| error(Some(self.move_number(i)), reason);
+            let board = history.board();
+            let colour = history.to_move();
+            let squares = board
+                .parse_move_squares(notation)
+                .ok_or_else(|| error(PdnErrorReason::BadSquare(notation.clone())))?;
+            let legal_moves = rules.generate_legal_moves(board, colour);
+            let candidates: Vec<&Movedef> = legal_moves
+                .iter()
+                .filter(|movedef| movedef.matches_squares(&squares))
+                .collect();
+            let movedef = match (candidates.as_slice(), board.get_piece(squares[0])) {
+                ([movedef], _) => (*movedef).clone(),
+                ([], None) => return Err(error(PdnErrorReason::NoPiece(notation.clone()))),
+                ([], Some(piece)) if piece.colour != colour => {
+                    return Err(error(PdnErrorReason::NotYourPiece(notation.clone())))
+                }
+                ([], Some(_)) => return Err(error(PdnErrorReason::IllegalMove(notation.clone()))),
+                _ => return Err(error(PdnErrorReason::AmbiguousMove(notation.clone()))),
+            };
+            history.push(&movedef);
+        }
+        Ok(history)
+    }
+}
+
+// reads every game in a PDN file. Comments, variations, move numbers and NAGs are skipped over,
+// only the tags and the moves of the main line are kept
+pub fn parse_pdn(text: &str, rules: &dyn Ruleset) -> Result<Vec<GameRecord>, PdnError> {
+    let first_mover = rules.first_to_move();
+    let mut games = Vec::new();
+    let mut game = GameRecord::new(first_mover, first_mover);
+    let mut chars = text.chars().peekable();
+    let error = |games: &Vec<GameRecord>, game: &GameRecord, reason| PdnError {
+        game: format!("game {} {}", games.len() + 1, game.name()),
+        move_number: if game.moves.is_empty() {
+            None
+        } else {
+            Some(game.move_number(game.moves.len() - 1))
+        },
+        reason,
+    };
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '[' => {
+                chars.next();
+                // tags after the moves belong to the next game
+                if !game.moves.is_empty() {
+                    games.push(std::mem::replace(
+                        &mut game,
+                        GameRecord::new(first_mover, first_mover),
+                    ));
+                }
+                let mut tag = String::new();
+                let mut quoted = false;
+                loop {
+                    match chars.next() {
+                        Some('"') => {
+                            quoted = !quoted;
+                            tag.push('"');
+                        }
+                        Some(']') if !quoted => break,
+                        Some(c) => tag.push(c),
+                        None => return Err(error(&games, &game, PdnErrorReason::UnclosedTag)),
+                    }
+                }
+                let (name, value) = tag
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((&tag, ""));
+                game.set_tag(name, value.trim().trim_matches('"'));
+                if name == "FEN" {
+                    let side = value.trim().trim_matches('"').get(..1);
+                    game.start_colour = match side {
+                        Some("W") => Colour::White,
+                        Some("B") => Colour::Black,
+                        _ => first_mover,
+                    };
+                }
+            }
+            '{' => {
+                if !chars.any(|c| c == '}') {
+                    return Err(error(&games, &game, PdnErrorReason::UnclosedComment));
+                }
+            }
+            ';' => {
+                chars.find(|&c| c == '\n');
+            }
+            '(' => {
+                // variations can nest, and hold comments with brackets of their own
+                let mut depth = 0;
+                loop {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some('{') => {
+                            if !chars.any(|c| c == '}') {
+                                return Err(error(&games, &game, PdnErrorReason::UnclosedComment));
+                            }
+                        }
+                        Some(_) => {}
+                        None => {
+                            return Err(error(&games, &game, PdnErrorReason::UnclosedVariation))
+                        }
+                    }
+                }
+            }
+            _ => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "[{(;".contains(c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                if RESULTS.contains(&token.as_str()) {
+                    if game.tag("Result").is_none() {
+                        game.set_tag("Result", &token);
+                    }
+                    games.push(std::mem::replace(
+                        &mut game,
+                        GameRecord::new(first_mover, first_mover),
+                    ));
+                    continue;
+                }
+                // NAGs, and move numbers like "12." or "12..." which may run into the move
+                if token.starts_with('$') {
+                    continue;
+                }
+                let notation = token
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .trim_start_matches('.');
+                let notation = if notation.len() < token.len() && token.contains('.') {
+                    notation
+                } else {
+                    token.as_str()
+                };
+                let notation = notation.trim_end_matches(['!', '?']);
+                if notation.is_empty() {
+                    continue;
+                }
+                let is_move = notation.split(['-', 'x']).count() >= 2
+                    && notation.split(['-', 'x']).all(|square| {
+                        !square.is_empty() && square.chars().all(|c| c.is_ascii_digit())
+                    });
+                if !is_move {
+                    return Err(error(
+                        &games,
+                        &game,
+                        PdnErrorReason::UnexpectedToken(token.clone()),
+                    ));
+                }
+                game.push_move(notation.to_string());
+            }
+        }
+    }
+    if !game.moves.is_empty() || !game.tags.is_empty() {
+        games.push(game);
+    }
+    Ok(games)
+}
+
+// reads a PDN file and replays every game in it, stopping at the first game with a problem
+pub fn load_pdn(text: &str, rules: &dyn Ruleset) -> Result<Vec<GameHistory>, PdnError> {
+    parse_pdn(text, rules)?
+        .iter()
+        .enumerate()
+        .map(|(i, game)| {
+            game.replay(rules).map_err(|mut error| {
+                error.game = format!("game {} {}", i + 1, error.game);
+                error
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Playable};
+    use crate::outcome::{DrawReason, GameOutcome, WinReason};
+    use crate::pdn::{load_pdn, parse_pdn, GameRecord, PdnErrorReason};
+    use crate::player::Colour;
+    use crate::rules::frisian::Frisian;
+    use crate::rules::italian::Italian;
+    use crate::rules::American;
+
+    const TWO_GAMES: &str = r#"
+[Event "Club night"]
+[White "Ann"]
+[Black "Bob"]
+[Result "1/2-1/2"]
+
+1. 11-15 {the old fourteenth} 23-19 2. 8-11 $1 22-17 (2. 24-20 {or this} (2. 26-22))
+2... 11-16!? ; a comment to the end of the line
+3. 17-13 1/2-1/2
+
+[Event "Club night"]
+[White "Cat"]
+[Black "Dan"]
+1.9-13 22-18 2.13-17 *
+"#;
+
+    #[test]
+    fn writes_tags_and_numbered_moves() {
+        let mut record = GameRecord::new(Colour::Black, Colour::Black);
+        record.set_tag("Event", "Casual game");
+        record.set_tag("Result", "*");
+        for notation in ["11-15", "23-19", "8-11"] {
+            record.push_move(notation.to_string());
+        }
+        record.set_result(Some(GameOutcome::Win(Colour::Black, WinReason::NoPieces)));
+        assert_eq!(
+            record.to_pdn(),
+            "[Event \"Casual game\"]\n[Result \"0-1\"]\n\n1. 11-15 23-19 2. 8-11 0-1\n\n"
+        );
+    }
+
+    #[test]
+    fn second_player_starting_and_long_games() {
+        let mut record = GameRecord::new(Colour::Black, Colour::White);
+        for _ in 0..30 {
+            record.push_move("22-18".to_string());
+        }
+        record.set_result(Some(GameOutcome::Draw(DrawReason::Repetition)));
+        let pdn = record.to_pdn();
+        assert!(pdn.starts_with("[Result \"1/2-1/2\"]\n\n1... 22-18 2. 22-18"));
+        assert!(pdn.trim_end().ends_with("1/2-1/2"));
+        assert!(pdn.lines().all(|line| line.len() <= 80));
+    }
+
+    #[test]
+    fn reads_several_games_past_comments_and_variations() {
+        let games = parse_pdn(TWO_GAMES, &American).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(
+            games[0].moves(),
+            ["11-15", "23-19", "8-11", "22-17", "11-16", "17-13"]
+        );
+        assert_eq!(games[0].tag("Result"), Some("1/2-1/2"));
+        assert_eq!(games[1].tag("White"), Some("Cat"));
+        assert_eq!(games[1].moves(), ["9-13", "22-18", "13-17"]);
+        let histories = load_pdn(TWO_GAMES, &American).unwrap();
+        assert_eq!(histories[1].to_move(), Colour::White);
+    }
+
+    #[test]
+    fn illegal_moves_name_the_game_and_move() {
+        // black has to take back on 16 rather than move another man
+        let pdn = "[White \"Ann\"]\n[Black \"Bob\"]\n1. 11-15 24-20 2. 15-19 23x16 3. 8-11 *";
+        let error = load_pdn(pdn, &American).unwrap_err();
+        assert_eq!(error.move_number, Some(3));
+        assert_eq!(
+            error.reason,
+            PdnErrorReason::IllegalMove("8-11".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "game 1 Ann vs Bob (?), move 3: 8-11 isn't a legal move"
+        );
+        let error = load_pdn("1. 11-15 15-19 *", &American).unwrap_err();
+        assert_eq!(
+            error.reason,
+            PdnErrorReason::NotYourPiece("15-19".to_string())
+        );
+        let error = load_pdn("1. 11-15 e3-f4 *", &American).unwrap_err();
+        assert_eq!(
+            error.reason,
+            PdnErrorReason::UnexpectedToken("e3-f4".to_string())
+        );
+        assert_eq!(error.move_number, Some(1));
+    }
+
+    #[test]
+    fn written_games_read_back() {
+        let mut record = GameRecord::new(Colour::Black, Colour::White);
+        record.set_tag("FEN", "W:W18,K32:BK1,14");
+        record.push_move("18x9".to_string());
+        record.push_move("1-6".to_string());
+        let histories = load_pdn(&record.to_pdn(), &American).unwrap();
+        let board = histories[0].board();
+        assert_eq!(board.get_all_colour_pieces(Colour::Black).len(), 1);
+        assert!(board.get_piece(board.square_loc(6).unwrap()).is_some());
+        assert_ne!(board, &Board::new());
+    }
+
+    #[test]
+    fn set_up_games_replay_on_the_variant_board() {
+        // Italian numbers the light squares of its mirrored board
+        let pdn = "[FEN \"W:W21,22,23:B9,10,13\"]\n1. 22-18 13x22 *";
+        let histories = load_pdn(pdn, &Italian).unwrap();
+        let board = histories[0].board();
+        assert_eq!(board.playable(), Playable::Light);
+        assert_eq!(board.get_all_colour_pieces(Colour::White).len(), 2);
+        let taker = board.get_piece(board.square_loc(22).unwrap()).unwrap();
+        assert_eq!(taker.colour, Colour::Black);
+        // Frisian keeps counting king moves from a set up position
+        let histories = load_pdn("[FEN \"W:WK46:BK5\"]\n1. 46-41 *", &Frisian).unwrap();
+        assert_eq!(
+            histories[0]
+                .board()
+                .king_streak(Colour::White)
+                .unwrap()
+                .moves,
+            1
+        );
+    }
+}
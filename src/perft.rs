@@ -0,0 +1,132 @@
+use crate::board::Board;
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::Ruleset;
+
+// the number of move sequences `depth` plies long from the position, the standard check on a
+// move generator. Counts to compare against are published for the common variants
+pub fn perft<R: Ruleset + ?Sized>(rules: &R, board: &mut Board, side: Colour, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = rules.generate_legal_moves(board, side);
+    // the last ply only needs counting, not playing
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0;
+    for movedef in moves.iter() {
+        let undo = board.make_move(movedef);
+        nodes += perft(rules, board, side.other(), depth - 1);
+        board.unmake_move(undo);
+    }
+    nodes
+}
+
+// perft split by the first move, which narrows a wrong count down to the move that causes it
+pub fn divide<R: Ruleset + ?Sized>(
+    rules: &R,
+    board: &mut Board,
+    side: Colour,
+    depth: u32,
+) -> Vec<(Movedef, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    rules
+        .generate_legal_moves(board, side)
+        .into_iter()
+        .map(|movedef| {
+            let undo = board.make_move(&movedef);
+            let nodes = perft(rules, board, side.other(), depth - 1);
+            board.unmake_move(undo);
+            (movedef, nodes)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fen::parse_fen;
+    use crate::perft::{divide, perft};
+    use crate::player::Colour;
+    use crate::rules::{American, Ruleset};
+
+    #[test]
+    fn start_position() {
+        // the published counts for American checkers
+        let counts = [7, 49, 302, 1469, 7361, 36768, 179740, 845931];
+        let mut board = American.starting_position();
+        for (depth, &count) in (1..).zip(counts.iter()) {
+            assert_eq!(perft(&American, &mut board, Colour::Black, depth), count);
+        }
+        assert_eq!(board, American.starting_position());
+    }
+
+    #[test]
+    fn tricky_positions() {
+        // every move worked out by hand from the diagram rather than taken from the engine, so a
+        // bug the generators share can't slip through
+        let positions: [(&str, &[&str]); 5] = [
+            // a king with capture loops that come back through its own start square
+            (
+                "B:W6,7,14,15,22,23:BK10",
+                &[
+                    "10x1",
+                    "10x17x26x19x10x1",
+                    "10x17x26x19x10x3",
+                    "10x19x26x17x10x1",
+                    "10x19x26x17x10x3",
+                    "10x3",
+                ],
+            ),
+            // a king with single, double and branching captures
+            (
+                "W:WK18:B7,8,14,15,22,23",
+                &["18x11x2", "18x11x4", "18x25", "18x27", "18x9"],
+            ),
+            // a man crowned by a capture has to stop there
+            ("B:W26,27,K30,K31:B18,19,22,23", &["23x32"]),
+            // every man has a double jump, most with a choice of second jump
+            (
+                "B:W17,18,19,20,25,26,27,28:B9,10,11,12,13,14,15,16",
+                &[
+                    "13x22x29", "13x22x31", "14x21x30", "14x23x30", "14x23x32", "15x22x29",
+                    "15x22x31", "15x24x31", "16x23x30", "16x23x32",
+                ],
+            ),
+            // white is completely blocked in
+            ("W:W10,11,12,13,K3,K4:B1,2,5,6,7,8,9,14,K32", &[]),
+        ];
+        for (fen, moves) in positions {
+            let (mut board, side) = parse_fen(fen, &American).unwrap();
+            let mut found: Vec<String> = divide(&American, &mut board, side, 1)
+                .iter()
+                .map(|(movedef, _)| board.move_string(movedef))
+                .collect();
+            found.sort();
+            assert_eq!(found, moves, "{}", fen);
+            assert_eq!(perft(&American, &mut board, side, 1), moves.len() as u64);
+        }
+    }
+
+    #[test]
+    fn depth_zero_is_the_position_itself() {
+        let mut board = American.starting_position();
+        assert_eq!(perft(&American, &mut board, Colour::Black, 0), 1);
+        assert!(divide(&American, &mut board, Colour::Black, 0).is_empty());
+    }
+
+    #[test]
+    fn divide_adds_up_to_perft() {
+        let mut board = American.starting_position();
+        let split = divide(&American, &mut board, Colour::Black, 5);
+        assert_eq!(split.len(), 7);
+        assert_eq!(split.iter().map(|(_, nodes)| nodes).sum::<u64>(), 7361);
+        let notation: Vec<String> = split
+            .iter()
+            .map(|(movedef, _)| board.move_string(movedef))
+            .collect();
+        assert!(notation.contains(&"11-15".to_string()));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::ballot::BALLOTS;
+use crate::bitboard::{self, american_moves};
+use crate::board::{Board, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+
+pub mod frisian;
+pub mod giveaway;
+pub mod international;
+pub mod italian;
+pub mod russian;
+pub mod turkish;
+
+use frisian::Frisian;
+use giveaway::Giveaway;
+use international::International;
+use italian::Italian;
+use russian::Russian;
+use turkish::Turkish;
+
+// white starts at the top of the board so moves down it, black moves up
+pub const DIAGONALS: [(i32, i32); 4] = [(1, -1), (1, 1), (-1, -1), (-1, 1)];
+
+pub fn forward_diagonals(colour: Colour) -> &'static [(i32, i32)] {
+    match colour {
+        Colour::White => &DIAGONALS[..2],
+        Colour::Black => &DIAGONALS[2..],
+    }
+}
+
+// everything that differs between checkers variants. The move generator is written
+// against these hooks so a new variant only has to describe how it differs
+pub trait Ruleset {
+    fn name(&self) -> &'static str;
+
+    // the GameType tag other checkers software knows the variant by in a PDN file
+    fn pdn_game_type(&self) -> Option<u32> {
+        None
+    }
+
+    fn starting_position(&self) -> Board;
+
+    fn first_to_move(&self) -> Colour;
+
+    // directions a piece can step one square in when it isn't capturing
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)];
+
+    // directions a piece can jump in, the same ones it moves in unless a variant says otherwise
+    fn capture_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        self.move_directions(piece)
+    }
+
+    fn mandatory_capture(&self) -> bool {
+        true
+    }
+
+    // a man that reaches the king row part way through a capture stops there
+    fn crowning_ends_move(&self) -> bool {
+        true
+    }
+
+    // when crowning doesn't end the move, whether the man is crowned on the spot and
+    // carries on capturing as a king rather than as a man
+    fn promotes_mid_capture(&self) -> bool {
+        false
+    }
+
+    // taking a king crowns the man that took it
+    fn captured_king_crowns(&self) -> bool {
+        false
+    }
+
+    fn promotes(&self, board: &Board, loc: usize, colour: Colour) -> bool {
+        board.piece_get_crowned(loc, colour)
+    }
+
+    // captured pieces come off the board as they are jumped rather than once the move is over,
+    // so they don't block the rest of the capture. A capture can't then turn straight back
+    fn removes_captured_immediately(&self) -> bool {
+        false
+    }
+
+    // kings move and capture any distance along a line rather than a single square
+    fn flying_kings(&self) -> bool {
+        false
+    }
+
+    fn can_capture(&self, _capturer: &Piece, _victim: &Piece) -> bool {
+        true
+    }
+
+    // narrows down which of the available capture chains a player may choose, any by default
+    fn select_captures(&self, _board: &Board, captures: Vec<Movedef>) -> Vec<Movedef> {
+        captures
+    }
+
+    // in a misere game the side left without pieces or moves wins rather than loses
+    fn misere(&self) -> bool {
+        false
+    }
+
+    // how good the position looks for `colour`, used at the leaves of the search
+    fn evaluate(&self, board: &Board, colour: Colour) -> i32 {
+        board.static_evaluation(colour)
+    }
+
+    // moves per side without a capture or man move before the game is drawn
+    fn draw_move_limit(&self) -> Option<u32> {
+        Some(40)
+    }
+
+    // the openings drawn from when playing ballots, none if the variant doesn't use them
+    fn ballots(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // every move the side has that doesn't capture, whether or not it may play them
+    fn quiet_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        plain_moves(self, board, colour)
+    }
+
+    fn generate_legal_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        standard_moves(self, board, colour)
+    }
+}
+
+// single steps, or slides for a flying king, onto empty squares
+pub fn plain_moves<R: Ruleset + ?Sized>(rules: &R, board: &Board, colour: Colour) -> Vec<Movedef> {
+    let mut moves: Vec<Movedef> = Vec::new();
+    for piece in board.pieces(colour) {
+        let range = if piece.king && rules.flying_kings() {
+            board.size
+        } else {
+            1
+        };
+        for move_vec in rules.move_directions(&piece).iter() {
+            for new_index in ray(board, piece.loc, *move_vec).take(range) {
+                if board.get_piece(new_index).is_some() {
+                    break;
+                }
+                moves.push(Movedef::new(piece.loc, new_index));
+            }
+        }
+    }
+    moves
+}
+
+// the move generator every ruleset shares, also callable from a variant that overrides
+// generate_legal_moves to add rules of its own on top
+pub fn standard_moves<R: Ruleset + ?Sized>(
+    rules: &R,
+    board: &Board,
+    colour: Colour,
+) -> Vec<Movedef> {
+    let mut legal_moves = rules.quiet_moves(board, colour);
+    let mut jumps: Vec<Movedef> = Vec::new();
+    for piece in board.pieces(colour) {
+        find_jumps(
+            rules,
+            board,
+            &piece,
+            piece.loc,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut jumps,
+        );
+    }
+    if jumps.is_empty() {
+        return legal_moves;
+    }
+    let mut jumps = rules.select_captures(board, jumps);
+    // when capturing is compulsory any jump available rules out every plain move
+    if !rules.mandatory_capture() {
+        jumps.append(&mut legal_moves);
+    }
+    jumps
+}
+
+// the generator for variants that move and capture like American checkers. The 8x8 board fits
+// in 32-bit bitboards, which are a good deal quicker to generate moves on
+pub fn american_style_moves<R: Ruleset + ?Sized>(
+    rules: &R,
+    board: &Board,
+    colour: Colour,
+) -> Vec<Movedef> {
+    if bitboard::fits(board) {
+        american_moves(board, colour, rules.captured_king_crowns())
+    } else {
+        standard_moves(rules, board, colour)
+    }
+}
+
+// every variant on offer in the setup menu, the default first
+pub fn variants() -> Vec<Box<dyn Ruleset>> {
+    vec![
+        Box::new(American),
+        Box::new(International::new()),
+        Box::new(Russian),
+        Box::new(Italian),
+        Box::new(Turkish),
+        Box::new(Giveaway),
+        Box::new(International::canadian()),
+        Box::new(Frisian),
+    ]
+}
+
+// the squares met walking from `loc` in direction `dir` until the edge of the board
+pub fn ray(board: &Board, loc: usize, dir: (i32, i32)) -> impl Iterator<Item = usize> + '_ {
+    let (row, col) = board.get_row_col_from_index(loc);
+    (1..)
+        .map(move |step| (row as i32 + step * dir.0, col as i32 + step * dir.1))
+        .take_while(|&(row, col)| board.inside_board(row as usize, col as usize))
+        .map(|(row, col)| board.get_index_from_row_col(row as usize, col as usize))
+}
+
+// depth first search for every capture chain the piece can make from `from`,
+// only a chain that can't be extended any further is a legal move
+fn find_jumps<R: Ruleset + ?Sized>(
+    rules: &R,
+    board: &Board,
+    piece: &Piece,
+    from: usize,
+    path: &mut Vec<usize>,
+    taken: &mut Vec<usize>,
+    jumps: &mut Vec<Movedef>,
+) {
+    let removed = rules.removes_captured_immediately();
+    // the jumping piece has already left its start square so it may land there again
+    let empty = |loc: usize, taken: &[usize]| {
+        loc == piece.loc || board.get_piece(loc).is_none() || removed && taken.contains(&loc)
+    };
+    let flying = piece.king && rules.flying_kings();
+    // the direction of the last jump, which can't be turned straight back on
+    let (row, col) = board.get_row_col_from_index(from);
+    let (last_row, last_col) =
+        board.get_row_col_from_index(*path.iter().rev().nth(1).unwrap_or(&piece.loc));
+    let last_dir = (
+        (row as i32 - last_row as i32).signum(),
+        (col as i32 - last_col as i32).signum(),
+    );
+    let mut extended = false;
+    for move_vec in rules.capture_directions(piece).iter() {
+        if removed
+            && !path.is_empty()
+            && (move_vec.0.signum(), move_vec.1.signum()) == (-last_dir.0, -last_dir.1)
+        {
+            continue;
+        }
+        let mut line = ray(board, from, *move_vec);
+        // a flying king may cross any number of empty squares to reach the piece it takes
+        let target = if flying {
+            line.find(|&loc| !empty(loc, taken))
+        } else {
+            line.next().filter(|&loc| !empty(loc, taken))
+        };
+        let piece_to_take = match target {
+            Some(loc) => loc,
+            None => continue,
+        };
+        // unless the variant removes them straight away, captured pieces stay on the board
+        // until the move is over so can't be jumped twice
+        let victim = board.get_piece(piece_to_take).unwrap();
+        if victim.colour == piece.colour
+            || taken.contains(&piece_to_take)
+            || !rules.can_capture(piece, victim)
+        {
+            continue;
+        }
+        let landings = line
+            .take_while(|&loc| empty(loc, taken))
+            .take(if flying { board.size } else { 1 })
+            .collect::<Vec<usize>>();
+        // a flying king has to land where it can carry on capturing if any square beyond the
+        // piece lets it, and may only stop on the line when none does
+        let mut line_jumps = Vec::new();
+        let mut carries_on = false;
+        for new_index in landings {
+            extended = true;
+            path.push(new_index);
+            taken.push(piece_to_take);
+            let mut chains = Vec::new();
+            let mut jumper = *piece;
+            let promotes = !piece.king && rules.promotes(board, new_index, piece.colour);
+            if promotes && rules.crowning_ends_move() {
+                // a man reaching the king row is crowned and that ends the move
+                chains.push(new_jump(rules, board, piece, path, taken));
+            } else {
+                if promotes && rules.promotes_mid_capture() {
+                    jumper.make_king();
+                }
+                find_jumps(rules, board, &jumper, new_index, path, taken, &mut chains);
+            }
+            let continues = chains.iter().any(|jump| jump.path.len() > path.len());
+            path.pop();
+            taken.pop();
+            if continues && !carries_on {
+                line_jumps.clear();
+                carries_on = true;
+            }
+            if continues || !carries_on {
+                line_jumps.extend(chains);
+            }
+        }
+        jumps.extend(line_jumps);
+    }
+    if !extended && !path.is_empty() {
+        jumps.push(new_jump(rules, board, piece, path, taken));
+    }
+}
+
+// the majority capture rule, only the chains taking the most pieces may be played
+pub fn most_captures(captures: Vec<Movedef>) -> Vec<Movedef> {
+    let most = captures
+        .iter()
+        .map(|m| m.taken_pieces.len())
+        .max()
+        .unwrap_or(0);
+    captures
+        .into_iter()
+        .filter(|m| m.taken_pieces.len() == most)
+        .collect()
+}
+
+fn new_jump<R: Ruleset + ?Sized>(
+    rules: &R,
+    board: &Board,
+    piece: &Piece,
+    path: &[usize],
+    taken: &[usize],
+) -> Movedef {
+    let mut movedef = Movedef::jump(piece.loc, path.to_vec(), taken.to_vec());
+    // `piece` is a crowned copy if it was promoted part way through, the board still has the man
+    let started_as_man = !board.get_piece(piece.loc).unwrap().king;
+    movedef.crowns = started_as_man
+        && (piece.king
+            || rules.captured_king_crowns()
+                && taken.iter().any(|&loc| board.get_piece(loc).unwrap().king));
+    movedef
+}
+
+// American / English checkers, the default ruleset
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct American;
+
+impl Ruleset for American {
+    fn name(&self) -> &'static str {
+        "American checkers"
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(21)
+    }
+
+    fn starting_position(&self) -> Board {
+        Board::new()
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::Black
+    }
+
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn ballots(&self) -> &'static [&'static str] {
+        &BALLOTS
+    }
+
+    fn generate_legal_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        american_style_moves(self, board, colour)
+    }
+}
@@ -0,0 +1,177 @@
+use crate::board::{Board, KingStreak, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::{forward_diagonals, plain_moves, Ruleset, DIAGONALS};
+
+// diagonal captures, then orthogonal ones - the next dark square along a row or column
+// is two squares away
+const ALL_CAPTURES: [(i32, i32); 8] = [
+    (1, -1),
+    (1, 1),
+    (-1, -1),
+    (-1, 1),
+    (2, 0),
+    (0, -2),
+    (0, 2),
+    (-2, 0),
+];
+
+// non-capturing moves in a row one king may make while its side still has men
+const KING_MOVE_LIMIT: u32 = 3;
+
+// Frisian draughts - 10x10 with flying kings, captures along rows and columns as well as
+// diagonals, and the capture worth the most must be taken
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Frisian;
+
+impl Frisian {
+    // a king is worth more than a man but less than two of them
+    fn capture_value(board: &Board, movedef: &Movedef) -> (u32, bool) {
+        let value = movedef
+            .taken_pieces
+            .iter()
+            .map(|&loc| {
+                if board.get_piece(loc).unwrap().king {
+                    199
+                } else {
+                    100
+                }
+            })
+            .sum();
+        // between captures of the same value, one made with a king comes first
+        (value, board.get_piece(movedef.start).unwrap().king)
+    }
+}
+
+impl Ruleset for Frisian {
+    fn name(&self) -> &'static str {
+        "Frisian draughts"
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(40)
+    }
+
+    fn starting_position(&self) -> Board {
+        let mut b = Board::with_start_rows(10, 4);
+        b.king_streaks = Some([KingStreak::default(); 2]);
+        b
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::White
+    }
+
+    // kings only ever move diagonally, the orthogonal lines are for capturing
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn capture_directions(&self, _piece: &Piece) -> &'static [(i32, i32)] {
+        &ALL_CAPTURES
+    }
+
+    fn crowning_ends_move(&self) -> bool {
+        false
+    }
+
+    fn flying_kings(&self) -> bool {
+        true
+    }
+
+    fn select_captures(&self, board: &Board, captures: Vec<Movedef>) -> Vec<Movedef> {
+        let best = captures
+            .iter()
+            .map(|movedef| Frisian::capture_value(board, movedef))
+            .max()
+            .unwrap();
+        captures
+            .into_iter()
+            .filter(|movedef| Frisian::capture_value(board, movedef) == best)
+            .collect()
+    }
+
+    // captures with the king are still allowed once it has used up its moves
+    fn quiet_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        let mut moves = plain_moves(self, board, colour);
+        let has_men = board
+            .get_all_colour_pieces(colour)
+            .iter()
+            .any(|piece| !piece.king);
+        if let Some(streak) = board.king_streak(colour) {
+            if has_men && streak.moves >= KING_MOVE_LIMIT {
+                moves.retain(|movedef| movedef.start != streak.loc);
+            }
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::movedef::Movedef;
+    use crate::player::Colour;
+    use crate::rules::frisian::Frisian;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn men_capture_orthogonally() {
+        let b = Frisian
+            .starting_position()
+            .cleared()
+            .with_pieces(&[(4, 1, Colour::White, false), (4, 3, Colour::Black, false)]);
+        let moves = Frisian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].path, vec![b.get_index_from_row_col(4, 5)]);
+    }
+
+    #[test]
+    fn king_outvalues_a_man() {
+        let b = Frisian.starting_position().cleared().with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (2, 7, Colour::White, false),
+            (3, 8, Colour::Black, true),
+        ]);
+        let moves = Frisian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].start, b.get_index_from_row_col(2, 7));
+    }
+
+    #[test]
+    fn two_men_outvalue_a_king() {
+        let b = Frisian.starting_position().cleared().with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (5, 4, Colour::Black, false),
+            (2, 7, Colour::White, false),
+            (3, 8, Colour::Black, true),
+        ]);
+        let moves = Frisian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].start, b.get_index_from_row_col(2, 1));
+    }
+
+    #[test]
+    fn king_limited_to_three_moves_in_a_row() {
+        let mut b = Frisian.starting_position().cleared().with_pieces(&[
+            (0, 1, Colour::White, true),
+            (1, 8, Colour::White, false),
+            (9, 0, Colour::Black, true),
+        ]);
+        let mut king = b.get_index_from_row_col(0, 1);
+        for step in [(1, 2), (0, 3), (1, 2)] {
+            let to = b.get_index_from_row_col(step.0, step.1);
+            b.ingest_movedef(&Movedef::new(king, to));
+            king = to;
+        }
+        assert_eq!(b.king_streak(Colour::White).unwrap().moves, 3);
+        let moves = Frisian.generate_legal_moves(&b, Colour::White);
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|m| m.start != king));
+    }
+}
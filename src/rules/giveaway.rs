@@ -0,0 +1,60 @@
+use crate::board::{Board, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::{american_style_moves, forward_diagonals, Ruleset, DIAGONALS};
+
+// giveaway checkers (antidraughts) - American moves and captures, but the first side to
+// lose all its pieces or be left without a move wins. Taking a king doesn't crown the man
+// that took it
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Giveaway;
+
+impl Ruleset for Giveaway {
+    fn name(&self) -> &'static str {
+        "Giveaway checkers"
+    }
+
+    fn starting_position(&self) -> Board {
+        Board::new()
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::Black
+    }
+
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn misere(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, board: &Board, colour: Colour) -> i32 {
+        board.giveaway_evaluation(colour)
+    }
+
+    fn generate_legal_moves(&self, board: &Board, colour: Colour) -> Vec<Movedef> {
+        american_style_moves(self, board, colour)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fen::parse_fen;
+    use crate::rules::giveaway::Giveaway;
+    use crate::rules::{standard_moves, Ruleset};
+
+    #[test]
+    fn taking_a_king_does_not_crown() {
+        let (board, to_move) = parse_fen("B:WK22,32:B18", &Giveaway).unwrap();
+        let moves = Giveaway.generate_legal_moves(&board, to_move);
+        assert_eq!(moves, standard_moves(&Giveaway, &board, to_move));
+        assert_eq!(moves.len(), 1);
+        assert!(!moves[0].crowns);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::board::{Board, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::{forward_diagonals, most_captures, Ruleset, DIAGONALS};
+
+// International draughts - 10x10, flying kings, men capture backwards and the
+// capture that takes the most pieces is compulsory. Canadian draughts plays the
+// same rules on a 12x12 board
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct International {
+    name: &'static str,
+    pdn_game_type: u32,
+    size: usize,
+}
+
+impl International {
+    pub const fn new() -> Self {
+        International {
+            name: "International draughts",
+            pdn_game_type: 20,
+            size: 10,
+        }
+    }
+
+    pub const fn canadian() -> Self {
+        International {
+            name: "Canadian draughts",
+            pdn_game_type: 27,
+            size: 12,
+        }
+    }
+}
+
+impl Default for International {
+    fn default() -> Self {
+        International::new()
+    }
+}
+
+impl Ruleset for International {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(self.pdn_game_type)
+    }
+
+    // the men fill every row but the middle two
+    fn starting_position(&self) -> Board {
+        Board::with_start_rows(self.size, self.size / 2 - 1)
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::White
+    }
+
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn capture_directions(&self, _piece: &Piece) -> &'static [(i32, i32)] {
+        &DIAGONALS
+    }
+
+    // a man only crowns if it finishes on the king row, passing over it mid-capture doesn't count
+    fn crowning_ends_move(&self) -> bool {
+        false
+    }
+
+    fn flying_kings(&self) -> bool {
+        true
+    }
+
+    fn select_captures(&self, _board: &Board, captures: Vec<Movedef>) -> Vec<Movedef> {
+        most_captures(captures)
+    }
+
+    fn draw_move_limit(&self) -> Option<u32> {
+        Some(25)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::player::Colour;
+    use crate::rules::international::International;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn starting_position() {
+        let b = International::new().starting_position();
+        assert_eq!(b.get_all_colour_pieces(Colour::White).len(), 20);
+        assert_eq!(b.get_all_colour_pieces(Colour::Black).len(), 20);
+        assert_eq!(
+            International::new()
+                .generate_legal_moves(&b, Colour::White)
+                .len(),
+            9
+        );
+    }
+
+    #[test]
+    fn men_capture_backwards() {
+        let b = Board::empty(10)
+            .with_pieces(&[(5, 4, Colour::White, false), (4, 3, Colour::Black, false)]);
+        let moves = International::new().generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].path, vec![b.get_index_from_row_col(3, 2)]);
+    }
+
+    #[test]
+    fn flying_king_moves() {
+        let b = Board::empty(10).with_pieces(&[(0, 1, Colour::White, true)]);
+        assert_eq!(
+            International::new()
+                .generate_legal_moves(&b, Colour::White)
+                .len(),
+            9
+        );
+    }
+
+    #[test]
+    fn flying_king_lands_anywhere_beyond() {
+        let b = Board::empty(10)
+            .with_pieces(&[(0, 1, Colour::White, true), (3, 4, Colour::Black, false)]);
+        let moves = International::new().generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 5);
+        assert!(moves
+            .iter()
+            .all(|m| m.taken_pieces == vec![b.get_index_from_row_col(3, 4)]));
+    }
+
+    #[test]
+    fn most_pieces_must_be_taken() {
+        let b = Board::empty(10).with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (2, 5, Colour::White, false),
+            (3, 6, Colour::Black, false),
+            (5, 6, Colour::Black, false),
+        ]);
+        let moves = International::new().generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].start, b.get_index_from_row_col(2, 5));
+        assert_eq!(moves[0].taken_pieces.len(), 2);
+    }
+
+    #[test]
+    fn canadian_starting_position() {
+        let canadian = International::canadian();
+        let b = canadian.starting_position();
+        assert_eq!(b.size, 12);
+        assert_eq!(b.get_all_colour_pieces(Colour::White).len(), 30);
+        assert_eq!(b.get_all_colour_pieces(Colour::Black).len(), 30);
+        assert_eq!(canadian.generate_legal_moves(&b, Colour::White).len(), 11);
+        assert!(b.as_string().contains("( 1)"));
+        assert!(b.as_string().contains("(72)"));
+    }
+}
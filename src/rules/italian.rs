@@ -0,0 +1,120 @@
+use crate::board::{Board, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::{forward_diagonals, Ruleset, DIAGONALS};
+
+// Italian draughts (dama) - played on a mirrored 8x8 board, men can't capture kings and
+// the capture played is picked by a strict order of priorities
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Italian;
+
+impl Italian {
+    // compared in order: pieces taken, kings taken, made with a king, how early the kings fall
+    fn capture_rank(board: &Board, movedef: &Movedef) -> (usize, usize, bool, Vec<bool>) {
+        let kings: Vec<bool> = movedef
+            .taken_pieces
+            .iter()
+            .map(|&loc| board.get_piece(loc).unwrap().king)
+            .collect();
+        (
+            movedef.taken_pieces.len(),
+            kings.iter().filter(|&&king| king).count(),
+            board.get_piece(movedef.start).unwrap().king,
+            kings,
+        )
+    }
+}
+
+impl Ruleset for Italian {
+    fn name(&self) -> &'static str {
+        "Italian draughts"
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(22)
+    }
+
+    // the board is turned so each player's single corner is on their right
+    fn starting_position(&self) -> Board {
+        Board::new().mirrored()
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::White
+    }
+
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn can_capture(&self, capturer: &Piece, victim: &Piece) -> bool {
+        capturer.king || !victim.king
+    }
+
+    fn select_captures(&self, board: &Board, captures: Vec<Movedef>) -> Vec<Movedef> {
+        let best = captures
+            .iter()
+            .map(|movedef| Italian::capture_rank(board, movedef))
+            .max()
+            .unwrap();
+        captures
+            .into_iter()
+            .filter(|movedef| Italian::capture_rank(board, movedef) == best)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::player::Colour;
+    use crate::rules::italian::Italian;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn men_cannot_capture_kings() {
+        let b = Board::empty(8)
+            .with_pieces(&[(2, 1, Colour::White, false), (3, 2, Colour::Black, true)]);
+        let moves = Italian.generate_legal_moves(&b, Colour::White);
+        assert!(moves.iter().all(|m| !m.is_capture()));
+    }
+
+    #[test]
+    fn king_capture_preferred_over_man() {
+        let b = Board::empty(8).with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (2, 5, Colour::White, true),
+            (3, 6, Colour::Black, false),
+        ]);
+        let moves = Italian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].start, b.get_index_from_row_col(2, 5));
+    }
+
+    #[test]
+    fn kings_taken_earliest_preferred() {
+        // the white king can take king then man, or man then king
+        let b = Board::empty(8).with_pieces(&[
+            (4, 3, Colour::White, true),
+            (3, 2, Colour::Black, true),
+            (1, 2, Colour::Black, false),
+            (5, 4, Colour::Black, false),
+            (5, 6, Colour::Black, true),
+        ]);
+        let moves = Italian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].taken_pieces[0], b.get_index_from_row_col(3, 2));
+    }
+
+    #[test]
+    fn mirrored_start() {
+        let b = Italian.starting_position();
+        assert!(b.get_piece(b.get_index_from_row_col(0, 0)).is_some());
+        assert_eq!(Italian.generate_legal_moves(&b, Colour::White).len(), 7);
+    }
+}
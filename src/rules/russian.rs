@@ -0,0 +1,111 @@
+use crate::board::{Board, Piece};
+use crate::player::Colour;
+use crate::rules::{forward_diagonals, Ruleset, DIAGONALS};
+
+// Russian draughts (shashki) - 8x8 with flying kings, men capture backwards, any
+// capture may be chosen and a man crowned mid-capture carries on jumping as a king
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Russian;
+
+impl Ruleset for Russian {
+    fn name(&self) -> &'static str {
+        "Russian draughts"
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(25)
+    }
+
+    fn starting_position(&self) -> Board {
+        Board::new()
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::White
+    }
+
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &DIAGONALS
+        } else {
+            forward_diagonals(piece.colour)
+        }
+    }
+
+    fn capture_directions(&self, _piece: &Piece) -> &'static [(i32, i32)] {
+        &DIAGONALS
+    }
+
+    fn crowning_ends_move(&self) -> bool {
+        false
+    }
+
+    fn promotes_mid_capture(&self) -> bool {
+        true
+    }
+
+    fn flying_kings(&self) -> bool {
+        true
+    }
+
+    fn draw_move_limit(&self) -> Option<u32> {
+        Some(15)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::fen::parse_fen;
+    use crate::player::Colour;
+    use crate::rules::russian::Russian;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn crowned_mid_capture_carries_on_as_king() {
+        let mut b = Board::empty(8).with_pieces(&[
+            (5, 2, Colour::White, false),
+            (6, 3, Colour::Black, false),
+            (5, 6, Colour::Black, false),
+        ]);
+        let moves = Russian.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(
+            moves[0].path,
+            vec![
+                b.get_index_from_row_col(7, 4),
+                b.get_index_from_row_col(4, 7)
+            ]
+        );
+        b.ingest_movedef(&moves[0]);
+        assert!(b.get_piece(b.get_index_from_row_col(4, 7)).unwrap().king);
+    }
+
+    #[test]
+    fn any_capture_may_be_chosen() {
+        let b = Board::empty(8).with_pieces(&[
+            (2, 1, Colour::White, false),
+            (3, 2, Colour::Black, false),
+            (2, 5, Colour::White, false),
+            (3, 6, Colour::Black, false),
+            (5, 6, Colour::Black, false),
+        ]);
+        let moves = Russian.generate_legal_moves(&b, Colour::White);
+        let mut counts: Vec<usize> = moves.iter().map(|m| m.taken_pieces.len()).collect();
+        counts.sort();
+        assert_eq!(counts, vec![1, 2]);
+    }
+
+    #[test]
+    fn flying_king_lands_where_it_can_carry_on() {
+        // jumping 23 the king could land on 18, 14, 9 or 5, but only from 18 can it go on to take 15
+        let (b, to_move) = parse_fen("W:WK32:B15,23", &Russian).unwrap();
+        let mut moves: Vec<String> = Russian
+            .generate_legal_moves(&b, to_move)
+            .iter()
+            .map(|movedef| b.move_string(movedef))
+            .collect();
+        moves.sort();
+        assert_eq!(moves, ["32x18x11", "32x18x4", "32x18x8"]);
+    }
+}
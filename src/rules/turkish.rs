@@ -0,0 +1,121 @@
+use crate::board::{Board, Piece};
+use crate::movedef::Movedef;
+use crate::player::Colour;
+use crate::rules::{most_captures, Ruleset};
+
+pub const ORTHOGONALS: [(i32, i32); 4] = [(1, 0), (0, -1), (0, 1), (-1, 0)];
+
+// Turkish draughts (dama) - every square is used, pieces move and capture orthogonally,
+// kings fly and captured pieces come off the board as they are jumped
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Turkish;
+
+impl Ruleset for Turkish {
+    fn name(&self) -> &'static str {
+        "Turkish draughts"
+    }
+
+    fn pdn_game_type(&self) -> Option<u32> {
+        Some(30)
+    }
+
+    // sixteen men each on the second and third rows, the back rows start empty
+    fn starting_position(&self) -> Board {
+        let mut b = Board::empty_every_square(8);
+        for i in 0..64 {
+            let (row, _col) = b.get_row_col_from_index(i);
+            if row == 1 || row == 2 {
+                b.set_piece(i, Some(Piece::new(i, Colour::White)));
+            } else if row == 5 || row == 6 {
+                b.set_piece(i, Some(Piece::new(i, Colour::Black)));
+            }
+        }
+        b
+    }
+
+    fn first_to_move(&self) -> Colour {
+        Colour::White
+    }
+
+    // men go forwards or sideways, never back
+    fn move_directions(&self, piece: &Piece) -> &'static [(i32, i32)] {
+        if piece.king {
+            &ORTHOGONALS
+        } else {
+            match piece.colour {
+                Colour::White => &ORTHOGONALS[..3],
+                Colour::Black => &ORTHOGONALS[1..],
+            }
+        }
+    }
+
+    fn crowning_ends_move(&self) -> bool {
+        false
+    }
+
+    fn removes_captured_immediately(&self) -> bool {
+        true
+    }
+
+    fn flying_kings(&self) -> bool {
+        true
+    }
+
+    fn select_captures(&self, _board: &Board, captures: Vec<Movedef>) -> Vec<Movedef> {
+        most_captures(captures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::player::Colour;
+    use crate::rules::turkish::Turkish;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn starting_position() {
+        let b = Turkish.starting_position();
+        assert_eq!(b.get_all_colour_pieces(Colour::White).len(), 16);
+        assert_eq!(b.get_all_colour_pieces(Colour::Black).len(), 16);
+        assert_eq!(Turkish.generate_legal_moves(&b, Colour::White).len(), 8);
+    }
+
+    #[test]
+    fn men_capture_sideways() {
+        let b = Board::empty_every_square(8)
+            .with_pieces(&[(3, 3, Colour::White, false), (3, 4, Colour::Black, false)]);
+        let moves = Turkish.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].path, vec![b.get_index_from_row_col(3, 5)]);
+    }
+
+    #[test]
+    fn captured_pieces_do_not_block() {
+        // the last jump runs up column 4 across the square of the first piece taken
+        let b = Board::empty_every_square(8).with_pieces(&[
+            (3, 1, Colour::White, true),
+            (3, 4, Colour::Black, false),
+            (5, 6, Colour::Black, false),
+            (6, 5, Colour::Black, false),
+            (1, 4, Colour::Black, false),
+        ]);
+        let moves = Turkish.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].taken_pieces.len(), 4);
+        assert_eq!(moves[0].end(), b.get_index_from_row_col(0, 4));
+    }
+
+    #[test]
+    fn no_turning_straight_back() {
+        // taking (0,4) then running back west to take (0,1) would be a 180 degree turn
+        let b = Board::empty_every_square(8).with_pieces(&[
+            (0, 3, Colour::White, true),
+            (0, 4, Colour::Black, false),
+            (0, 1, Colour::Black, false),
+        ]);
+        let moves = Turkish.generate_legal_moves(&b, Colour::White);
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|m| m.taken_pieces.len() == 1));
+    }
+}
@@ -0,0 +1,126 @@
+use std::mem;
+
+// the memory the AI's table gets unless told otherwise
+pub const DEFAULT_TABLE_MEGABYTES: usize = 16;
+
+// how a stored score relates to the position's true score. A search cut off by alpha-beta
+// only learns that the score is at least, or at most, what it found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableEntry {
+    pub key: u64,
+    // plies searched below the position
+    pub depth: i32,
+    pub bound: Bound,
+    pub score: i32,
+    // the best move's index in the order the move generator gives the moves
+    pub best_move: Option<u16>,
+}
+
+// positions the search has already scored, keyed by Zobrist key. The table has a fixed
+// number of slots and a position always goes in the slot its key picks
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    entries: Vec<Option<TableEntry>>,
+}
+
+impl TranspositionTable {
+    // as many slots as fit in `megabytes`, rounded down to a power of two. No memory at all
+    // gives a table that never stores anything
+    pub fn new(megabytes: usize) -> Self {
+        let slots = megabytes * 1024 * 1024 / mem::size_of::<Option<TableEntry>>();
+        let slots = match slots {
+            0 => 0,
+            _ => 1 << slots.ilog2(),
+        };
+        Self {
+            entries: vec![None; slots],
+        }
+    }
+
+    pub fn slots(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries[self.slot(key)].filter(|entry| entry.key == key)
+    }
+
+    // another position in the slot is always replaced, the newer one is more likely to come
+    // up again. The same position keeps whichever search went deeper
+    pub fn store(&mut self, entry: TableEntry) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let slot = self.slot(entry.key);
+        match self.entries[slot] {
+            Some(old) if old.key == entry.key && old.depth > entry.depth => {}
+            _ => self.entries[slot] = Some(entry),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_MEGABYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transposition::{Bound, TableEntry, TranspositionTable};
+
+    fn entry(key: u64, depth: i32, score: i32) -> TableEntry {
+        TableEntry {
+            key,
+            depth,
+            bound: Bound::Exact,
+            score,
+            best_move: None,
+        }
+    }
+
+    #[test]
+    fn stores_and_replaces() {
+        let mut table = TranspositionTable::new(1);
+        assert!(table.slots().is_power_of_two());
+        table.store(entry(5, 4, 10));
+        assert_eq!(table.probe(5), Some(entry(5, 4, 10)));
+        // a shallower search of the same position doesn't overwrite a deeper one
+        table.store(entry(5, 2, 20));
+        assert_eq!(table.probe(5).unwrap().score, 10);
+        // a different position in the same slot does, and isn't mistaken for the old one
+        let clash = 5 + table.slots() as u64;
+        assert_eq!(table.probe(clash), None);
+        table.store(entry(clash, 1, 30));
+        assert_eq!(table.probe(5), None);
+        assert_eq!(table.probe(clash).unwrap().score, 30);
+        table.clear();
+        assert_eq!(table.probe(clash), None);
+    }
+
+    #[test]
+    fn no_memory_stores_nothing() {
+        let mut table = TranspositionTable::new(0);
+        assert_eq!(table.slots(), 0);
+        table.store(entry(5, 4, 10));
+        assert_eq!(table.probe(5), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::board::{Bits, KingStreak};
+use crate::player::Colour;
+
+const SQUARES: usize = Bits::BITS as usize;
+
+// splitmix64, enough to spread the keys evenly and simple enough to run at compile time
+const fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// a key for each kind of piece on each square - white men, white kings, black men, black kings
+const PIECE_KEYS: [[u64; SQUARES]; 4] = {
+    let mut keys = [[0; SQUARES]; 4];
+    let mut kind = 0;
+    while kind < 4 {
+        let mut square = 0;
+        while square < SQUARES {
+            keys[kind][square] = mix((kind * SQUARES + square) as u64);
+            square += 1;
+        }
+        kind += 1;
+    }
+    keys
+};
+
+const WHITE_TO_MOVE: u64 = mix(4 * SQUARES as u64);
+
+// `square` counts from 0, the bit the square has in the board's bitboards
+pub fn piece_key(square: usize, colour: Colour, king: bool) -> u64 {
+    let kind = match colour {
+        Colour::White => 0,
+        Colour::Black => 2,
+    } + usize::from(king);
+    PIECE_KEYS[kind][square]
+}
+
+pub fn side_key(to_move: Colour) -> u64 {
+    match to_move {
+        Colour::White => WHITE_TO_MOVE,
+        Colour::Black => 0,
+    }
+}
+
+// the king move limit makes a streak part of the position in the variants that track one
+pub fn streak_key(colour: Colour, streak: KingStreak) -> u64 {
+    if streak.moves == 0 {
+        return 0;
+    }
+    let colour = match colour {
+        Colour::White => 0,
+        Colour::Black => 1,
+    };
+    mix(WHITE_TO_MOVE ^ ((streak.loc as u64) << 16 | (streak.moves as u64) << 1 | colour))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::fen::{parse_fen, to_fen};
+    use crate::player::Colour;
+    use crate::rules::{American, Ruleset};
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn keys_follow_the_moves() {
+        let mut rng = rand::thread_rng();
+        let mut board = Board::new();
+        let mut colour = Colour::Black;
+        for _ in 0..100 {
+            let moves = American.generate_legal_moves(&board, colour);
+            let movedef = match moves.choose(&mut rng) {
+                Some(movedef) => movedef,
+                None => break,
+            };
+            board.ingest_movedef(movedef);
+            colour = colour.other();
+            // the key kept up to date move by move matches one worked out from scratch
+            let (fresh, _) = parse_fen(&to_fen(&board, colour), &American).unwrap();
+            assert_eq!(board.zobrist_key(colour), fresh.zobrist_key(colour));
+        }
+    }
+
+    #[test]
+    fn transpositions_share_a_key() {
+        let play = |moves: [&str; 4]| {
+            let mut board = Board::new();
+            let mut colour = Colour::Black;
+            for notation in moves {
+                let squares = board.parse_move_squares(notation).unwrap();
+                let movedef = American
+                    .generate_legal_moves(&board, colour)
+                    .into_iter()
+                    .find(|movedef| movedef.matches_squares(&squares))
+                    .unwrap();
+                board.ingest_movedef(&movedef);
+                colour = colour.other();
+            }
+            board
+        };
+        let a = play(["11-15", "24-20", "9-14", "22-17"]);
+        let b = play(["9-14", "22-17", "11-15", "24-20"]);
+        assert_eq!(a.zobrist_key(Colour::Black), b.zobrist_key(Colour::Black));
+        assert_ne!(a.zobrist_key(Colour::Black), a.zobrist_key(Colour::White));
+        assert_ne!(
+            a.zobrist_key(Colour::Black),
+            Board::new().zobrist_key(Colour::Black)
+        );
+    }
+}